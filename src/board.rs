@@ -0,0 +1,288 @@
+use eframe::egui;
+use crate::code::Code;
+use crate::consts;
+use crate::crypto::CODE_LENGTH;
+use crate::feedback::Feedback;
+use crate::settings::{PegLabels, Settings};
+
+const PEG_RADIUS: f32 = 10.0;
+const PEG_SPACING: f32 = 30.0;
+const FEEDBACK_RADIUS: f32 = 6.0;
+const FEEDBACK_SPACING: f32 = 15.0;
+// seconds for all feedback pegs of a row to drop in
+const FEEDBACK_ANIMATION: f32 = 0.6;
+
+// the shape of a game and the player's display settings, everything drawing it needs
+pub struct BoardConfig {
+    pub rows: usize,
+    pub code_length: usize,
+    pub palette: Vec<egui::Color32>,
+    pub labels: PegLabels,
+    pub high_contrast_feedback: bool,
+}
+
+impl BoardConfig {
+    pub fn new(settings: &Settings) -> Self {
+        BoardConfig {
+            rows: settings.guesses,
+            code_length: CODE_LENGTH,
+            palette: settings.palette.colors().to_vec(),
+            labels: settings.labels,
+            high_contrast_feedback: settings.high_contrast_feedback,
+        }
+    }
+
+    pub fn empty_row(&self) -> Vec<Option<u8>> {
+        vec![None; self.code_length]
+    }
+}
+
+pub struct BoardRow {
+    pub pegs: Vec<Option<u8>>,
+    pub feedback: Option<Feedback>,
+    // the feedback's proof did not verify
+    pub rejected: bool,
+}
+
+// a color peg with its label on top, or a dark hole for an empty slot
+fn paint_peg(painter: &egui::Painter, config: &BoardConfig, center: egui::Pos2, radius: f32, peg: Option<u8>) {
+    let Some(col) = peg else {
+        painter.circle_filled(center, PEG_RADIUS / 2.0, consts::EMPTY_PEG);
+        return;
+    };
+    let color = config.palette[col as usize];
+    painter.circle_filled(center, radius, color);
+    let label = match config.labels {
+        PegLabels::None => return,
+        PegLabels::Letters => Code::letter(col).to_string(),
+        PegLabels::Numbers => (col + 1).to_string(),
+    };
+    // dark text on light pegs and the other way round
+    let [r, g, b, _] = color.to_array();
+    let light = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32 > 140.0;
+    let text_color = if light { egui::Color32::BLACK } else { egui::Color32::WHITE };
+    painter.text(center, egui::Align2::CENTER_CENTER, label, egui::FontId::monospace(radius * 1.2), text_color);
+}
+
+// empty slots are drawn as dark holes
+pub fn pegs_ui(ui: &mut egui::Ui, config: &BoardConfig, pegs: &[Option<u8>]) -> egui::Response {
+    let (response, painter) = ui.allocate_painter(
+        egui::Vec2::new(PEG_SPACING * config.code_length as f32, PEG_SPACING),
+        egui::Sense::hover(),
+    );
+    for (j, peg) in pegs.iter().enumerate() {
+        let center = response.rect.min + egui::Vec2::new(PEG_SPACING * (j as f32 + 0.5), PEG_SPACING / 2.0);
+        paint_peg(&painter, config, center, PEG_RADIUS, *peg);
+    }
+    response
+}
+
+pub fn code_ui(ui: &mut egui::Ui, config: &BoardConfig, code: &Code) -> egui::Response {
+    pegs_ui(ui, config, &code.colors.map(Some))
+}
+
+// the pegs drop in one after another once the feedback arrives
+pub fn feedback_ui(ui: &mut egui::Ui, config: &BoardConfig, id: egui::Id, feedback: Option<Feedback>) {
+    let (response, painter) = ui.allocate_painter(
+        egui::Vec2::new(FEEDBACK_SPACING * config.code_length as f32, PEG_SPACING),
+        egui::Sense::hover(),
+    );
+    let shown = ui.ctx().animate_bool_with_time(id, feedback.is_some(), FEEDBACK_ANIMATION);
+    let Some(feedback) = feedback else {
+        return;
+    };
+    for j in 0..config.code_length {
+        let grown = (shown * config.code_length as f32 - j as f32).clamp(0.0, 1.0);
+        let center = response.rect.min + egui::Vec2::new(FEEDBACK_SPACING * (j as f32 + 0.5), PEG_SPACING / 2.0);
+        let radius = FEEDBACK_RADIUS * grown;
+        let black = j < feedback.black as usize;
+        let white = !black && j < feedback.common() as usize;
+        if config.high_contrast_feedback {
+            // solid for the right place, a ring for the right color, nothing otherwise
+            let stroke = egui::Stroke::new(2.0, ui.visuals().strong_text_color());
+            if black {
+                painter.circle(center, radius, stroke.color, stroke);
+            } else if white {
+                painter.circle_stroke(center, radius - 1.0, stroke);
+            }
+        } else {
+            let color = if black {
+                egui::Color32::BLACK
+            } else if white {
+                egui::Color32::WHITE
+            } else {
+                consts::EMPTY_PEG
+            };
+            painter.circle_filled(center, radius, color);
+        }
+    }
+    response.on_hover_text(feedback.to_string());
+}
+
+// what is being dragged over the board: a color from the palette or a peg from the active row
+#[derive(Clone, Copy)]
+enum Dragged {
+    Color(u8),
+    Slot(usize),
+}
+
+// the color goes into the selected slot, or else the first empty one; the selection moves on to the next empty slot
+fn place(pegs: &mut [Option<u8>], selected: &mut Option<usize>, col: u8) {
+    let Some(slot) = selected.or_else(|| pegs.iter().position(Option::is_none)) else {
+        return;
+    };
+    pegs[slot] = Some(col);
+    *selected = (slot + 1..pegs.len()).chain(0..slot).find(|&j| pegs[j].is_none());
+}
+
+// the row being guessed: click a slot to select it, right-click to clear it, drag pegs to reorder them
+fn active_pegs_ui(ui: &mut egui::Ui, config: &BoardConfig, pegs: &mut [Option<u8>], selected: &mut Option<usize>) {
+    for j in 0..pegs.len() {
+        let (rect, response) = ui.allocate_exact_size(egui::Vec2::splat(PEG_SPACING), egui::Sense::click_and_drag());
+        let painter = ui.painter();
+        paint_peg(painter, config, rect.center(), PEG_RADIUS, pegs[j]);
+        if *selected == Some(j) || response.dnd_hover_payload::<Dragged>().is_some() {
+            painter.circle_stroke(rect.center(), PEG_RADIUS + 2.0, ui.visuals().selection.stroke);
+        }
+        if pegs[j].is_some() {
+            response.dnd_set_drag_payload(Dragged::Slot(j));
+        }
+        if response.clicked() {
+            *selected = if *selected == Some(j) { None } else { Some(j) };
+        }
+        if response.secondary_clicked() {
+            pegs[j] = None;
+            *selected = Some(j);
+        }
+        match response.dnd_release_payload::<Dragged>().map(|dragged| *dragged) {
+            Some(Dragged::Slot(from)) => pegs.swap(from, j),
+            Some(Dragged::Color(col)) => pegs[j] = Some(col),
+            None => {}
+        }
+    }
+}
+
+// every color once, click one to place it or drag it onto a slot; hovering shows its keys
+fn palette_ui(ui: &mut egui::Ui, config: &BoardConfig) -> Option<u8> {
+    let mut clicked = None;
+    ui.horizontal(|ui| {
+        for col in 0..config.palette.len() as u8 {
+            let (rect, response) = ui.allocate_exact_size(egui::Vec2::splat(PEG_SPACING), egui::Sense::click_and_drag());
+            let radius = if response.hovered() { PEG_RADIUS + 2.0 } else { PEG_RADIUS };
+            paint_peg(ui.painter(), config, rect.center(), radius, Some(col));
+            response.dnd_set_drag_payload(Dragged::Color(col));
+            if response.on_hover_text(format!("{} / {}", Code::letter(col), col + 1)).clicked() {
+                clicked = Some(col);
+            }
+        }
+    });
+    clicked
+}
+
+// the dragged peg follows the pointer above everything else
+fn dragged_ui(ui: &egui::Ui, config: &BoardConfig, pegs: &[Option<u8>]) {
+    let col = match egui::DragAndDrop::payload::<Dragged>(ui.ctx()).map(|dragged| *dragged) {
+        Some(Dragged::Color(col)) => Some(col),
+        Some(Dragged::Slot(j)) => pegs.get(j).copied().flatten(),
+        None => None,
+    };
+    if let (Some(col), Some(pos)) = (col, ui.ctx().pointer_interact_pos()) {
+        let painter = ui.ctx().layer_painter(egui::LayerId::new(egui::Order::Tooltip, egui::Id::new("dragged peg")));
+        paint_peg(&painter, config, pos, PEG_RADIUS, Some(col));
+    }
+}
+
+// letters pick colors, Backspace clears the selected or the last peg, Escape the whole row, Enter confirms
+fn keyboard(ui: &egui::Ui, config: &BoardConfig, pegs: &mut [Option<u8>], selected: &mut Option<usize>) -> bool {
+    for col in 0..config.palette.len() as u8 {
+        let key = egui::Key::from_name(&Code::letter(col).to_string()).unwrap();
+        if ui.input(|u| u.key_pressed(key)) {
+            place(pegs, selected, col);
+        }
+    }
+    if ui.input(|u| u.key_pressed(egui::Key::Backspace)) {
+        let slot = selected.filter(|&j| pegs[j].is_some()).or_else(|| pegs.iter().rposition(Option::is_some));
+        if let Some(slot) = slot {
+            pegs[slot] = None;
+            *selected = Some(slot);
+        }
+    }
+    if ui.input(|u| u.key_pressed(egui::Key::Escape)) {
+        pegs.fill(None);
+        *selected = None;
+    }
+    ui.input(|u| u.key_pressed(egui::Key::Enter))
+}
+
+// all rows of a game in a scroll area with the palette below; the active row is highlighted, rejected ones are red,
+// and can be edited with mouse and keyboard. Returns the row the player confirmed
+pub fn board_ui(ui: &mut egui::Ui, config: &BoardConfig, id: egui::Id, rows: &mut [BoardRow], active: Option<usize>) -> Option<usize> {
+    let mut confirmed = None;
+    // the active row is cleared, or refilled from the row before it
+    let (mut clear, mut repeat) = (false, false);
+    let selected_id = id.with("selected");
+    let mut selected = ui.ctx().data(|data| data.get_temp::<Option<usize>>(selected_id)).flatten();
+    egui::ScrollArea::vertical().max_height(PEG_SPACING * 10.0).show(ui, |ui| {
+        for (i, row) in rows.iter_mut().enumerate().take(config.rows) {
+            let is_active = active == Some(i);
+            let fill = if row.rejected {
+                egui::Color32::RED.gamma_multiply(0.3)
+            } else if is_active {
+                ui.visuals().selection.bg_fill.gamma_multiply(0.3)
+            } else {
+                egui::Color32::TRANSPARENT
+            };
+            let frame = egui::Frame::none().inner_margin(2.0).rounding(4.0).fill(fill);
+            let inner = frame.show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add_enabled_ui(is_active, |ui| {
+                        ui.label(format!("Guess {}: ", i + 1));
+                        if is_active {
+                            active_pegs_ui(ui, config, &mut row.pegs, &mut selected);
+                        } else {
+                            pegs_ui(ui, config, &row.pegs);
+                        }
+                        let complete = row.pegs.iter().all(Option::is_some);
+                        if ui.add_enabled(complete, egui::Button::new("Confirm")).clicked() {
+                            confirmed = Some(i);
+                        }
+                        if is_active {
+                            clear = ui.button("Clear").on_hover_text("Esc").clicked();
+                            repeat = i > 0 && ui.button("Previous").on_hover_text("start from the previous guess").clicked();
+                        }
+                    });
+                    feedback_ui(ui, config, id.with(i), row.feedback);
+                });
+            });
+            // only when a row becomes active, so the player can still scroll around
+            let scrolled_id = id.with("scrolled");
+            if is_active && ui.ctx().data(|data| data.get_temp::<usize>(scrolled_id)) != Some(i) {
+                inner.response.scroll_to_me(None);
+                ui.ctx().data_mut(|data| data.insert_temp(scrolled_id, i));
+            }
+        }
+    });
+    ui.add_space(10.0);
+    let clicked = ui.vertical_centered(|ui| palette_ui(ui, config)).inner;
+    let previous = active.filter(|_| repeat).and_then(|i| i.checked_sub(1)).map(|i| rows[i].pegs.clone());
+    if let Some(row) = active.and_then(|i| rows.get_mut(i)) {
+        if clear || previous.is_some() {
+            row.pegs = previous.unwrap_or_else(|| config.empty_row());
+            selected = None;
+        }
+        if let Some(col) = clicked {
+            place(&mut row.pegs, &mut selected, col);
+        }
+        if keyboard(ui, config, &mut row.pegs, &mut selected) && row.pegs.iter().all(Option::is_some) {
+            confirmed = active;
+        }
+        dragged_ui(ui, config, &row.pegs);
+    } else {
+        selected = None;
+    }
+    if confirmed.is_some() {
+        selected = None;
+    }
+    ui.ctx().data_mut(|data| data.insert_temp(selected_id, selected));
+    confirmed
+}
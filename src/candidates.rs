@@ -0,0 +1,181 @@
+use std::sync::OnceLock;
+use crate::code::{Code, CODE_COUNT};
+use crate::feedback::{Feedback, ANSWER_COUNT};
+use crate::scoring::feedback;
+
+// beyond this many codes a table row per guess costs more memory than it saves,
+// so answers are scored on the fly instead
+const TABLE_LIMIT: usize = 1 << 16;
+
+fn rows() -> Option<&'static [OnceLock<Box<[Feedback]>>]> {
+    static ROWS: OnceLock<Vec<OnceLock<Box<[Feedback]>>>> = OnceLock::new();
+    (CODE_COUNT <= TABLE_LIMIT).then(|| ROWS.get_or_init(|| (0..CODE_COUNT).map(|_| OnceLock::new()).collect()).as_slice())
+}
+
+// answers to a guess for every secret, filled the first time a large set is scored against the guess
+fn table_row(guess: &Code) -> Option<&'static [Feedback]> {
+    let row = rows()?[guess.index()].get_or_init(|| Code::all().map(|secret| feedback(&secret, guess)).collect());
+    Some(row)
+}
+
+fn cached_row(guess: &Code) -> Option<&'static [Feedback]> {
+    rows()?[guess.index()].get().map(|row| &**row)
+}
+
+// same answer as `scoring::feedback`, looked up when the guess already has a row
+pub fn answer(secret: &Code, guess: &Code) -> Feedback {
+    match cached_row(guess) {
+        Some(row) => row[secret.index()],
+        None => feedback(secret, guess),
+    }
+}
+
+// a set of codes as one bit per code index
+#[derive(Clone, Debug)]
+pub struct Candidates {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl Candidates {
+    pub fn all() -> Self {
+        let mut words = vec![u64::MAX; CODE_COUNT.div_ceil(64)];
+        if CODE_COUNT % 64 != 0 {
+            *words.last_mut().unwrap() = (1 << (CODE_COUNT % 64)) - 1;
+        }
+        Candidates { words, len: CODE_COUNT }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Code> + '_ {
+        self.indices().map(Code::from_index)
+    }
+
+    pub fn first(&self) -> Option<Code> {
+        self.iter().next()
+    }
+
+    // a row scores every code, which only pays off while the set is a good part of them
+    fn answers<'a>(&self, guess: &'a Code) -> impl Fn(usize) -> Feedback + 'a {
+        let row = if self.len * 16 >= CODE_COUNT { table_row(guess) } else { cached_row(guess) };
+        move |index| match row {
+            Some(row) => row[index],
+            None => feedback(&Code::from_index(index), guess),
+        }
+    }
+
+    fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(w, &word)| {
+            let mut rest = word;
+            std::iter::from_fn(move || {
+                (rest != 0).then(|| {
+                    let bit = rest.trailing_zeros() as usize;
+                    rest &= rest - 1;
+                    w * 64 + bit
+                })
+            })
+        })
+    }
+
+    fn insert(&mut self, index: usize) {
+        self.words[index / 64] |= 1 << (index % 64);
+        self.len += 1;
+    }
+
+    // keep only the codes which would have given this answer to the guess
+    pub fn retain(&mut self, guess: &Code, given: Feedback) {
+        let answers = self.answers(guess);
+        let mut kept = Candidates { words: vec![0; self.words.len()], len: 0 };
+        for index in self.indices().filter(|&index| answers(index) == given) {
+            kept.insert(index);
+        }
+        *self = kept;
+    }
+
+    // how many of the codes would give each answer to the guess, indexed by `Feedback::index`
+    pub fn answer_counts(&self, guess: &Code) -> [usize; ANSWER_COUNT] {
+        let answers = self.answers(guess);
+        let mut counts = [0; ANSWER_COUNT];
+        for index in self.indices() {
+            counts[answers(index).index()] += 1;
+        }
+        counts
+    }
+
+    // the codes split by the answer they would give to the guess, leaving out answers no code gives
+    pub fn partition(&self, guess: &Code) -> Vec<(Feedback, Candidates)> {
+        let answers = self.answers(guess);
+        let mut parts: [Option<Candidates>; ANSWER_COUNT] = Default::default();
+        for index in self.indices() {
+            parts[answers(index).index()]
+                .get_or_insert_with(|| Candidates { words: vec![0; self.words.len()], len: 0 })
+                .insert(index);
+        }
+        parts.into_iter().enumerate()
+            .filter_map(|(answer, part)| Some((Feedback::from_index(answer), part?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+    use super::*;
+
+    fn code(text: &str) -> Code {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn all_holds_every_code() {
+        let all = Candidates::all();
+        assert_eq!(all.len(), CODE_COUNT);
+        assert!(all.iter().eq(Code::all()));
+    }
+
+    #[test]
+    fn cached_answers_are_scored_answers() {
+        for guess in [code("aabb"), code("abcd")] {
+            // scoring every code fills the guess's row
+            Candidates::all().answer_counts(&guess);
+            assert!(cached_row(&guess).is_some());
+            for secret in Code::all() {
+                assert_eq!(answer(&secret, &guess), feedback(&secret, &guess));
+            }
+        }
+    }
+
+    #[test]
+    fn retain_keeps_the_codes_giving_the_answer() {
+        let mut candidates = Candidates::all();
+        let guess = code("aabb");
+        let given = feedback(&code("abcd"), &guess);
+        let count = candidates.answer_counts(&guess)[given.index()];
+        candidates.retain(&guess, given);
+        assert_eq!(candidates.len(), count);
+        assert_eq!(candidates.iter().count(), count);
+        assert!(candidates.iter().all(|secret| feedback(&secret, &guess) == given));
+    }
+
+    #[test]
+    fn partitions_add_up_to_the_answer_counts() {
+        let mut candidates = Candidates::all();
+        candidates.retain(&code("aabb"), feedback(&code("abcd"), &code("aabb")));
+        // some of the codes as well as all of them
+        for candidates in [Candidates::all(), candidates] {
+            let guess = code("abce");
+            let counts = candidates.answer_counts(&guess);
+            let parts = candidates.partition(&guess);
+            assert_eq!(parts.iter().map(|(_, part)| part.len()).sum::<usize>(), candidates.len());
+            assert_eq!(parts.len(), counts.iter().filter(|&&count| count > 0).count());
+            for (answer, part) in parts {
+                assert_eq!(part.len(), counts[answer.index()]);
+                assert!(part.iter().all(|secret| feedback(&secret, &guess) == answer));
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::fmt;
+use std::path::PathBuf;
+use eframe::egui;
+use crate::code::Code;
+use crate::commitment::{Commitment, Opening};
+use crate::feedback::Feedback;
+
+// the check a host failed; any of them forfeits the game to the player
+#[derive(Clone, Copy, Debug)]
+pub enum Cheat {
+    Commitment(Commitment),
+    Answer { row: usize, guess: Code, answer: Feedback },
+    // the revealed code is not the committed one
+    Opening(Opening),
+    // the code was never broken and the host would not show it
+    Withheld,
+}
+
+impl fmt::Display for Cheat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cheat::Commitment(commitment) => write!(f,
+                "The proof for the commitment {} does not verify, so there may be no valid code behind it.", commitment),
+            Cheat::Answer { row, guess, answer } => write!(f,
+                "The proof for the answer {} to guess {} ({}) does not verify, so the answer is not the one the committed code gives.",
+                answer, row + 1, guess),
+            Cheat::Opening(opening) => write!(f,
+                "The revealed code {} does not hash to the commitment, so it is not the code the answers were about.", opening.code),
+            Cheat::Withheld => write!(f, "The host refused to reveal its code although it was never broken."),
+        }
+    }
+}
+
+// the dialog shown once a cheat is detected, with the evidence for export
+pub struct CheatReport {
+    pub cheat: Cheat,
+    open: bool,
+    saved: Option<Result<PathBuf, String>>,
+}
+
+impl CheatReport {
+    pub fn new(cheat: Cheat) -> Self {
+        CheatReport { cheat, open: true, saved: None }
+    }
+
+    // the transcript is only put together when the player asks for it
+    pub fn ui(&mut self, ctx: &egui::Context, commitment: &Commitment, transcript: impl FnOnce() -> String) {
+        let mut open = self.open;
+        let (mut save, mut copy) = (false, false);
+        egui::Window::new("Cheating detected")
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.colored_label(egui::Color32::RED, self.cheat.to_string());
+                ui.label("The game is forfeited in your favor.");
+                ui.horizontal(|ui| {
+                    save = ui.button("Save transcript").clicked();
+                    copy = ui.button("Copy transcript").clicked();
+                });
+                match &self.saved {
+                    Some(Ok(path)) => ui.label(format!("saved to {}", path.display())),
+                    Some(Err(err)) => ui.colored_label(egui::Color32::RED, err),
+                    None => ui.label(""),
+                };
+            });
+        self.open = open;
+        if save || copy {
+            let text = format!("cheat: {}\n{}", self.cheat, transcript());
+            if save {
+                let path = PathBuf::from(format!("zk-mastermind-{}.txt", &commitment.to_string()[..16]));
+                self.saved = Some(std::fs::write(&path, text).map(|_| path).map_err(|err| err.to_string()));
+            } else {
+                ctx.copy_text(text);
+            }
+        }
+    }
+
+    pub fn reopen(&mut self) {
+        self.open = true;
+    }
+}
@@ -0,0 +1,152 @@
+use std::io::{self, BufRead, Write};
+use crate::code::{Code, COLOR_NAMES};
+use crate::daily::Daily;
+use crate::host::{HonestHost, Host, HostError};
+use crate::match_play::{Match, Player};
+use crate::inspector::{Inspector, Statement};
+use crate::solver::Solver;
+use crate::seed::GameSeed;
+use crate::HostFactory;
+
+// only once the game is over, as the seed gives the code away
+fn print_seed(seed: GameSeed) {
+    println!("Seed {}, --seed {} replays the code and commitment", seed, seed);
+}
+
+// plays one game against the host in the terminal
+pub fn play(new_host: HostFactory, seed: GameSeed, budget: usize) -> Result<(), HostError> {
+    play_host(new_host(seed), budget, &mut Inspector::default())?;
+    print_seed(seed);
+    Ok(())
+}
+
+// today's code, or that of another date; the transcript is saved for comparing with other players
+pub fn play_daily(daily: Daily, budget: usize) -> Result<(), HostError> {
+    println!("Daily challenge of {}, the code is committed to {}", daily.date, daily.commitment());
+    let mut inspector = Inspector::default();
+    let (broken, guesses) = play_host(Box::new(HonestHost::new(daily.seed)), budget, &mut inspector)?;
+    let result = if broken { "broken" } else { "not broken" };
+    match daily.save_transcript(&format!("{}result: {} in {} guesses\n", inspector.export(), result, guesses)) {
+        Ok(path) => println!("Transcript saved to {}", path.display()),
+        Err(err) => println!("Could not save the transcript: {}", err),
+    }
+    Ok(())
+}
+
+// every proof goes to the inspector; returns whether the code was broken and in how many guesses
+fn play_host(mut host: Box<dyn Host>, budget: usize, inspector: &mut Inspector) -> Result<(bool, usize), HostError> {
+    let (commitment, proof) = host.get_hash_with_proof()?;
+    if !inspector.record(Statement::Commitment(commitment), proof) {
+        println!("The host's commitment does not verify, refusing to play.");
+        return Ok((false, 0));
+    }
+    println!("Host committed to {}", commitment);
+    println!("Colors: {}", COLOR_NAMES.iter().enumerate().map(|(i, name)| format!("{} = {}", Code::letter(i as u8), name)).collect::<Vec<_>>().join(", "));
+
+    let mut lines = io::stdin().lock().lines();
+    let mut guesses = 0;
+    while guesses < budget {
+        print!("Guess {}: ", guesses + 1);
+        io::stdout().flush().ok();
+        let Some(Ok(line)) = lines.next() else {
+            return Ok((false, guesses));
+        };
+        let guess = match line.parse::<Code>() {
+            Ok(guess) => guess,
+            Err(err) => {
+                println!("{}", err);
+                continue;
+            }
+        };
+        let (answer, proof) = host.guess(guess)?;
+        if !inspector.record(Statement::Guess { guess, commitment, answer }, proof) {
+            println!("The proof for {} does not verify, the host is cheating!", answer);
+            return Ok((false, guesses));
+        }
+        guesses += 1;
+        println!("{} {}", guess, answer);
+        if answer.is_win() {
+            println!("You won in {} guesses!", guesses);
+            return Ok((true, guesses));
+        }
+    }
+    match host.surrender() {
+        Some(opening) if opening.opens(&commitment) => println!("You lost! The code was {}", opening.code),
+        Some(opening) => println!("You lost! The host claims the code was {}, but that does not match its commitment", opening.code),
+        None => println!("You lost! The host does not reveal its code"),
+    }
+    Ok((false, guesses))
+}
+
+// None once the input ends
+fn read_code(lines: &mut impl Iterator<Item = io::Result<String>>, prompt: &str) -> Option<Code> {
+    loop {
+        print!("{}", prompt);
+        io::stdout().flush().ok();
+        let Some(Ok(line)) = lines.next() else {
+            return None;
+        };
+        match line.parse::<Code>() {
+            Ok(code) => return Some(code),
+            Err(err) => println!("{}", err),
+        }
+    }
+}
+
+// a match against the computer, you are player one and make the first code
+// round i is played under the seed derived with i
+pub fn play_match(new_host: HostFactory, seed: GameSeed, rounds: usize, guesses: usize) -> Result<(), HostError> {
+    let mut game = Match::new(rounds, guesses);
+    let mut lines = io::stdin().lock().lines();
+    while !game.is_over() {
+        let round = game.transcripts().len() + 1;
+        let computer_guesses = game.codemaker() == Player::One;
+        let mut host: Box<dyn Host> = if computer_guesses {
+            println!("Round {} of {}: you make the code", round, game.rounds());
+            let Some(code) = read_code(&mut lines, "Your code: ") else {
+                return Ok(());
+            };
+            Box::new(HonestHost::with_code(code, seed.derive(round as u64)))
+        } else {
+            println!("Round {} of {}: you break the code", round, game.rounds());
+            new_host(seed.derive(round as u64))
+        };
+        let (commitment, proof) = host.get_hash_with_proof()?;
+        if !game.start_round(commitment, &proof) {
+            println!("The commitment for {} does not verify, the codemaker is cheating!", commitment);
+        }
+        let mut solver = Solver::new();
+        while game.wants_guess() {
+            let prompt = format!("Guess {}: ", game.transcripts().last().unwrap().exchanges.len() + 1);
+            let guess = if computer_guesses {
+                let guess = solver.next_guess().ok_or(HostError::ProtocolViolation("no code is consistent with the answers"))?;
+                print!("{}", prompt);
+                guess
+            } else {
+                let Some(guess) = read_code(&mut lines, &prompt) else {
+                    return Ok(());
+                };
+                guess
+            };
+            let (answer, proof) = host.guess(guess)?;
+            solver.learn(&guess, answer);
+            if game.record(guess, answer, &proof) {
+                println!("{} {}", guess, answer);
+            } else {
+                println!("{} {}: the proof does not verify, the codemaker is cheating!", guess, answer);
+            }
+        }
+        game.finish_round(host.surrender());
+        println!("{}", game.transcripts().last().unwrap());
+        let [you, computer] = game.scores();
+        println!("Score: you {}, computer {}\n", you, computer);
+    }
+    let [you, computer] = game.scores();
+    println!("{}", match you.cmp(&computer) {
+        std::cmp::Ordering::Greater => "You won the match!",
+        std::cmp::Ordering::Less => "The computer won the match!",
+        std::cmp::Ordering::Equal => "The match is a draw.",
+    });
+    print_seed(seed);
+    Ok(())
+}
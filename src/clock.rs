@@ -0,0 +1,132 @@
+use std::time::Duration;
+use web_time::Instant;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeLimit {
+    #[default]
+    None,
+    // seconds for all guesses together
+    PerGame(u64),
+    // seconds for each guess, the unused time does not carry over
+    PerGuess(u64),
+}
+
+// the player's thinking time; it only runs on the player's turn, never while the host proves
+pub struct Clock {
+    limit: TimeLimit,
+    // in the turns before the current one
+    used: Duration,
+    // in the current turn before it was paused
+    turn_used: Duration,
+    turn_started: Option<Instant>,
+}
+
+impl Clock {
+    pub fn new(limit: TimeLimit) -> Self {
+        Clock { limit, used: Duration::ZERO, turn_used: Duration::ZERO, turn_started: None }
+    }
+
+    // starts a turn, or resumes a paused one
+    pub fn start(&mut self) {
+        self.turn_started.get_or_insert_with(Instant::now);
+    }
+
+    // the time since the turn was last started, passed on to the rest so the tests can make it up
+    fn running(&self) -> Duration {
+        self.turn_started.map_or(Duration::ZERO, |started| started.elapsed())
+    }
+
+    // holds the clock while the player looks elsewhere, the turn goes on once it is started again
+    pub fn pause(&mut self) {
+        self.pause_after(self.running());
+    }
+
+    fn pause_after(&mut self, running: Duration) {
+        if self.turn_started.take().is_some() {
+            self.turn_used += running;
+        }
+    }
+
+    // ends the turn
+    pub fn stop(&mut self) {
+        self.stop_after(self.running());
+    }
+
+    fn stop_after(&mut self, running: Duration) {
+        self.pause_after(running);
+        self.used += std::mem::take(&mut self.turn_used);
+    }
+
+    // None without a limit
+    pub fn remaining(&self) -> Option<Duration> {
+        self.remaining_after(self.running())
+    }
+
+    fn remaining_after(&self, running: Duration) -> Option<Duration> {
+        let turn = self.turn_used + running;
+        match self.limit {
+            TimeLimit::None => None,
+            TimeLimit::PerGame(seconds) => Some(Duration::from_secs(seconds).saturating_sub(self.used + turn)),
+            TimeLimit::PerGuess(seconds) => Some(Duration::from_secs(seconds).saturating_sub(turn)),
+        }
+    }
+
+    pub fn is_out(&self) -> bool {
+        self.remaining() == Some(Duration::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+    use super::*;
+
+    fn secs(seconds: u64) -> Option<Duration> {
+        Some(Duration::from_secs(seconds))
+    }
+
+    #[test]
+    fn no_limit_never_runs_out() {
+        let mut clock = Clock::new(TimeLimit::None);
+        clock.start();
+        assert_eq!(clock.remaining_after(Duration::from_secs(1000)), None);
+    }
+
+    #[test]
+    fn guesses_start_afresh() {
+        let mut clock = Clock::new(TimeLimit::PerGuess(10));
+        clock.start();
+        assert_eq!(clock.remaining_after(Duration::from_secs(4)), secs(6));
+        clock.stop_after(Duration::from_secs(4));
+        assert_eq!(clock.remaining(), secs(10));
+        clock.start();
+        assert_eq!(clock.remaining_after(Duration::from_secs(12)), secs(0));
+    }
+
+    #[test]
+    fn games_carry_the_time_over() {
+        let mut clock = Clock::new(TimeLimit::PerGame(10));
+        clock.start();
+        clock.stop_after(Duration::from_secs(4));
+        assert_eq!(clock.remaining(), secs(6));
+        clock.start();
+        clock.stop_after(Duration::from_secs(3));
+        assert_eq!(clock.remaining(), secs(3));
+        clock.start();
+        assert_eq!(clock.remaining_after(Duration::from_secs(5)), secs(0));
+    }
+
+    #[test]
+    fn pauses_do_not_end_the_turn() {
+        let mut clock = Clock::new(TimeLimit::PerGuess(10));
+        clock.start();
+        clock.pause_after(Duration::from_secs(4));
+        assert_eq!(clock.remaining(), secs(6));
+        clock.start();
+        assert_eq!(clock.remaining_after(Duration::from_secs(3)), secs(3));
+        clock.stop_after(Duration::from_secs(3));
+        assert_eq!(clock.remaining(), secs(10));
+    }
+}
@@ -0,0 +1,146 @@
+use std::fmt;
+use std::str::FromStr;
+use rand::Rng;
+use crate::crypto::{CODE_LENGTH, COLOR_NUMBER};
+
+// number of distinct codes, each has a dense index below it
+pub const CODE_COUNT: usize = COLOR_NUMBER.pow(CODE_LENGTH as u32);
+
+pub const COLOR_NAMES: [&str; COLOR_NUMBER] = ["red", "orange", "yellow", "green", "cyan", "blue", "purple", "pink"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Code {
+    pub colors: [u8; CODE_LENGTH],
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseCodeError {
+    WrongLength(usize),
+    UnknownColor(String),
+}
+
+impl fmt::Display for ParseCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseCodeError::WrongLength(len) => write!(f, "expected {} colors, got {}", CODE_LENGTH, len),
+            ParseCodeError::UnknownColor(color) => write!(f, "unknown color {:?}", color),
+        }
+    }
+}
+
+impl std::error::Error for ParseCodeError {}
+
+impl Code {
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Code { colors: std::array::from_fn(|_| rng.gen_range(0..COLOR_NUMBER as u8)) }
+    }
+
+    // all codes in lexicographic order, "aaaa" first
+    pub fn all() -> impl Iterator<Item = Code> {
+        (0..CODE_COUNT).map(Code::from_index)
+    }
+
+    // position in `Code::all`, reading the colors as digits in base COLOR_NUMBER
+    pub fn index(&self) -> usize {
+        self.colors.iter().fold(0, |index, &col| index * COLOR_NUMBER + col as usize)
+    }
+
+    pub fn from_index(mut index: usize) -> Self {
+        let mut colors = [0; CODE_LENGTH];
+        for color in colors.iter_mut().rev() {
+            *color = (index % COLOR_NUMBER) as u8;
+            index /= COLOR_NUMBER;
+        }
+        Code { colors }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.colors.iter().all(|&col| (col as usize) < COLOR_NUMBER)
+    }
+
+    pub fn letter(color: u8) -> char {
+        (b'a' + color) as char
+    }
+}
+
+// a color is a letter "a".."h", a digit "1".."8" or its name
+fn parse_color(token: &str) -> Result<u8, ParseCodeError> {
+    let token = token.to_lowercase();
+    let color = match token.as_bytes() {
+        [c @ b'a'..=b'z'] => Some(c - b'a'),
+        [d @ b'1'..=b'9'] => Some(d - b'1'),
+        _ => COLOR_NAMES.iter().position(|name| *name == token).map(|col| col as u8),
+    };
+    match color {
+        Some(col) if (col as usize) < COLOR_NUMBER => Ok(col),
+        _ => Err(ParseCodeError::UnknownColor(token)),
+    }
+}
+
+// accepts "abcd", "1234" and separated forms like "red green blue pink" or "a, b, c, d"
+impl FromStr for Code {
+    type Err = ParseCodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let tokens: Vec<String> = if s.contains(|c: char| c.is_whitespace() || c == ',') {
+            s.split(|c: char| c.is_whitespace() || c == ',').filter(|t| !t.is_empty()).map(str::to_owned).collect()
+        } else {
+            s.chars().map(String::from).collect()
+        };
+        if tokens.len() != CODE_LENGTH {
+            return Err(ParseCodeError::WrongLength(tokens.len()));
+        }
+        let mut colors = [0; CODE_LENGTH];
+        for (color, token) in colors.iter_mut().zip(&tokens) {
+            *color = parse_color(token)?;
+        }
+        Ok(Code { colors })
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.colors.iter().try_for_each(|&col| write!(f, "{}", Code::letter(col)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+    use super::*;
+
+    #[test]
+    fn codes_parse_in_every_form() {
+        let code = Code { colors: [0, 1, 2, 7] };
+        for text in ["abch", "ABCH", "1238", " abch ", "red orange yellow pink", "Red, Orange, Yellow, Pink", "a, b, c, h", "1 2 3 8", "a 2 yellow h"] {
+            assert_eq!(text.parse(), Ok(code), "{:?}", text);
+        }
+        assert_eq!(code.to_string().parse(), Ok(code));
+    }
+
+    #[test]
+    fn bad_codes_say_why() {
+        assert_eq!("abc".parse::<Code>(), Err(ParseCodeError::WrongLength(3)));
+        assert_eq!("abcde".parse::<Code>(), Err(ParseCodeError::WrongLength(5)));
+        assert_eq!("red green blue".parse::<Code>(), Err(ParseCodeError::WrongLength(3)));
+        assert_eq!("".parse::<Code>(), Err(ParseCodeError::WrongLength(0)));
+        assert_eq!("abci".parse::<Code>(), Err(ParseCodeError::UnknownColor("i".to_owned())));
+        assert_eq!("abc9".parse::<Code>(), Err(ParseCodeError::UnknownColor("9".to_owned())));
+        assert_eq!("abcé".parse::<Code>(), Err(ParseCodeError::UnknownColor("é".to_owned())));
+        assert_eq!("red green blue Black".parse::<Code>(), Err(ParseCodeError::UnknownColor("black".to_owned())));
+    }
+
+    #[test]
+    fn indices_follow_all() {
+        for (index, code) in Code::all().enumerate() {
+            assert_eq!(code.index(), index);
+            assert_eq!(Code::from_index(index), code);
+            assert!(code.is_valid());
+        }
+        assert_eq!(Code::from_index(0).to_string(), "aaaa");
+        assert_eq!(Code::from_index(CODE_COUNT - 1).to_string(), "hhhh");
+        assert_eq!("abcd".parse::<Code>().unwrap().index(), (8 + 2) * 8 + 3);
+    }
+}
@@ -5,40 +5,28 @@ use ark_r1cs_std::fields::FieldVar;
 use ark_r1cs_std::uint8::UInt8;
 use ark_relations::ns;
 use ark_relations::r1cs::{ConstraintSystemRef, Result};
-use ark_std::iterable::Iterable;
 use std::cmp::Ordering;
-use sha2::{Digest, Sha256};
-use ark_r1cs_std::eq::EqGadget;
 
-use super::crypto::{Code, CODE_LENGTH, COLOR_NUMBER, compute_hash};
+use super::code::Code;
+use super::commitment::Opening;
+use super::crypto::{CODE_LENGTH, COLOR_NUMBER, compute_hash, enforce_packed_hash, pack_hash, HASH_CHUNKS};
 
 type CircuitField = Fr;
 
+// built only from an opening, so the hash always matches the code and salt
 #[derive(Clone, Debug)]
 pub struct CodeDeclarationCircuit {
-    pub code: Code,
-    pub salt: [u8; 32],
-    pub hash: [u8; 32],
+    code: Code,
+    salt: [u8; 32],
+    hash: [u8; 32],
 }
 
-impl From<Code> for CodeDeclarationCircuit {
-    fn from(code: Code) -> Self {
-        let salt = rand::random::<[u8; 32]>();
-
-        let mut hasher = Sha256::new();
-
-        code
-            .colors
-            .iter()
-            .for_each(|col| hasher.update([col]));
-        hasher.update(salt);
-
-        let hash_result = hasher.finalize();
-
+impl From<&Opening> for CodeDeclarationCircuit {
+    fn from(opening: &Opening) -> Self {
         CodeDeclarationCircuit {
-            code,
-            salt,
-            hash: hash_result.into(),
+            code: opening.code,
+            salt: opening.salt,
+            hash: opening.commit().hash,
         }
     }
 }
@@ -58,17 +46,13 @@ impl ark_relations::r1cs::ConstraintSynthesizer<CircuitField> for CodeDeclaratio
             .salt
             .map(|bit| UInt8::new_witness(ns!(cs, "salt"), || Ok(bit)).unwrap());
 
-        let hash_vars: [UInt8<CircuitField>; 32] = self
-            .hash
-            .map(|bit| UInt8::new_input(ns!(cs, "hash"), || Ok(bit)).unwrap());
+        let hash_vars: [FpVar<CircuitField>; HASH_CHUNKS] = pack_hash(&self.hash)
+            .map(|chunk| FpVar::new_input(ns!(cs, "hash"), || Ok(chunk)).unwrap());
 
 
         let digest_var = compute_hash(&code_place_vars, &salt_vars)?;
 
-        hash_vars
-            .iter()
-            .zip(digest_var.0)
-            .for_each(|(h1, h2)| h1.enforce_equal(&h2).unwrap());
+        enforce_packed_hash(&digest_var, &hash_vars)?;
 
         code_place_vars.iter().for_each(|col| {
             FpVar::enforce_cmp(col, &FpVar::zero(), Ordering::Greater, true).unwrap();
@@ -0,0 +1,211 @@
+use std::sync::mpsc::{self, Receiver};
+use eframe::egui;
+use crate::code::Code;
+use crate::commitment::Commitment;
+use crate::board::{self, BoardConfig};
+use crate::feedback::Feedback;
+use crate::handle::{HostEndpoint, HostHandle, Request, Response, Served};
+use crate::host::{HonestHost, HostError};
+use crate::proof::{verify, verify_guess};
+use crate::seed::GameSeed;
+use crate::solver::Solver;
+use crate::HOST_TIMEOUT;
+
+// an input for the secret code, hidden when someone else is looking at the screen;
+// Some once the player commits to a valid code
+pub fn secret_input(ui: &mut egui::Ui, config: &BoardConfig, secret: &mut String, hidden: bool) -> Option<Code> {
+    let mut committed = None;
+    ui.horizontal(|ui| {
+        let edit = ui.add(egui::TextEdit::singleline(secret).password(hidden));
+        let code = secret.parse::<Code>().ok();
+        if let Some(code) = code.filter(|_| !hidden) {
+            board::code_ui(ui, config, &code);
+        }
+        let entered = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        if ui.add_enabled(code.is_some(), egui::Button::new("Commit")).clicked() || entered {
+            committed = code;
+        }
+    });
+    ui.label("Letters a-h, digits 1-8 or color names, e.g. \"red green blue pink\".");
+    committed
+}
+
+// one of the solver's guesses as the player sees it, with whether its proof checked out
+struct Turn {
+    guess: Code,
+    answer: Feedback,
+    verified: bool,
+}
+
+// the human picks the code, an honest host proves its answers and the solver guesses
+pub struct Codemaker {
+    secret: String,
+    host: Option<HostHandle>,
+    commitment: Option<(Commitment, bool)>,
+    solver: Solver,
+    turns: Vec<Turn>,
+    guesses: usize,
+    error: Option<HostError>,
+}
+
+impl Codemaker {
+    pub fn new(guesses: usize) -> Self {
+        Codemaker {
+            secret: String::new(),
+            host: None,
+            commitment: None,
+            solver: Solver::new(),
+            turns: Vec::new(),
+            guesses,
+            error: None,
+        }
+    }
+
+    fn start(&mut self, code: Code) {
+        // the player picked the code, only the salt and the proofs are left to chance
+        let mut host = HostHandle::spawn(Box::new(HonestHost::with_code(code, GameSeed::fresh()))).with_timeout(HOST_TIMEOUT);
+        self.error = host.send(Request::Commit).err();
+        self.host = Some(host);
+    }
+
+    fn is_over(&self) -> bool {
+        self.turns.len() == self.guesses || self.turns.last().is_some_and(|turn| turn.answer.is_win())
+    }
+
+    // the solver's next guess goes out as soon as the previous answer is in
+    fn guess_next(&mut self) {
+        if self.is_over() {
+            return;
+        }
+        let Some(host) = &mut self.host else {
+            return;
+        };
+        match self.solver.next_guess() {
+            Some(guess) => self.error = host.send(Request::Guess(guess)).err(),
+            None => self.error = Some(HostError::ProtocolViolation("no code is consistent with the answers")),
+        }
+    }
+
+    fn poll(&mut self) {
+        let Some((request, response)) = self.host.as_mut().and_then(HostHandle::poll) else {
+            return;
+        };
+        match (request, response) {
+            (_, Err(err)) => self.error = Some(err),
+            (Request::Commit, Ok(Response::Committed(commitment, proof))) => {
+                self.commitment = Some((commitment, verify(&commitment, &proof)));
+                self.guess_next();
+            }
+            (Request::Guess(guess), Ok(Response::Answered(answer, proof))) => {
+                let verified = self.commitment.is_some_and(|(commitment, _)| verify_guess(guess, &commitment, answer, &proof));
+                self.solver.learn(&guess, answer);
+                self.turns.push(Turn { guess, answer, verified });
+                self.guess_next();
+            }
+            _ => self.error = Some(HostError::ProtocolViolation("response does not fit the request")),
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, config: &BoardConfig) {
+        self.poll();
+        if self.host.is_none() {
+            ui.label("Pick the code for the computer to break:");
+            if let Some(code) = secret_input(ui, config, &mut self.secret, false) {
+                self.start(code);
+            }
+            return;
+        }
+
+        match self.commitment {
+            Some((commitment, verified)) => {
+                ui.label(format!("Committed to {}", commitment));
+                ui.label(if verified { "✔ commitment proof verified" } else { "✘ commitment proof failed" });
+            }
+            None => {
+                ui.label("Committing…");
+            }
+        }
+        ui.add_space(10.0);
+        for (i, turn) in self.turns.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("Guess {}: ", i + 1));
+                board::code_ui(ui, config, &turn.guess);
+                board::feedback_ui(ui, config, ui.id().with(i), Some(turn.answer));
+                if turn.verified {
+                    ui.colored_label(egui::Color32::GREEN, "✔ proof verified");
+                } else {
+                    ui.colored_label(egui::Color32::RED, "✘ proof failed");
+                }
+            });
+        }
+        if self.host.as_ref().is_some_and(|host| host.pending().is_some()) {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(format!("proving… ({} codes still possible)", self.solver.remaining()));
+            });
+        }
+        if let Some(err) = &self.error {
+            ui.colored_label(egui::Color32::RED, err.to_string());
+        }
+        if self.is_over() {
+            ui.add_space(10.0);
+            ui.heading(match self.turns.last() {
+                Some(turn) if turn.answer.is_win() => format!("The computer broke your code in {} guesses", self.turns.len()),
+                _ => "The computer ran out of guesses".to_owned(),
+            });
+            if ui.button("New code").clicked() {
+                *self = Codemaker::new(self.guesses);
+            }
+        }
+    }
+}
+
+// the codemaker's own window when two people play on one machine: it only sees the
+// requests coming in over the host protocol and what its honest host answered
+pub struct CodemakerWindow {
+    secret: String,
+    code: Option<Code>,
+    endpoint: Option<HostEndpoint>,
+    served: Vec<Served>,
+    observer: Option<Receiver<Served>>,
+    seed: GameSeed,
+}
+
+impl CodemakerWindow {
+    pub fn new(endpoint: HostEndpoint, seed: GameSeed) -> Self {
+        CodemakerWindow { secret: String::new(), code: None, endpoint: Some(endpoint), served: Vec::new(), observer: None, seed }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, config: &BoardConfig) {
+        if let Some(observer) = &self.observer {
+            self.served.extend(observer.try_iter());
+        }
+        let Some(code) = self.code else {
+            ui.label("Pick the code for the other player to break:");
+            if let Some(code) = secret_input(ui, config, &mut self.secret, true) {
+                let (sender, observer) = mpsc::channel();
+                self.endpoint.take().unwrap().serve(Box::new(HonestHost::with_code(code, self.seed)), Some(sender));
+                self.observer = Some(observer);
+                self.code = Some(code);
+            }
+            return;
+        };
+        ui.horizontal(|ui| {
+            ui.label("Your code: ");
+            board::code_ui(ui, config, &code);
+        });
+        ui.add_space(10.0);
+        for (i, served) in self.served.iter().enumerate() {
+            match served {
+                Served::Committed(commitment) => ui.label(format!("committed to {}", commitment)),
+                Served::Answered(guess, answer) => ui.horizontal(|ui| {
+                    board::code_ui(ui, config, guess);
+                    board::feedback_ui(ui, config, ui.id().with(i), Some(*answer));
+                    ui.label("answered with a proof");
+                }).response,
+                Served::Surrendered => ui.label("revealed the code"),
+                Served::Failed(request) => ui.colored_label(egui::Color32::RED, format!("could not answer {:?}", request)),
+            };
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use std::fmt;
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use crate::code::Code;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Commitment {
+    pub hash: [u8; 32],
+}
+
+// everything needed to open a commitment; never leaves the host
+#[derive(Clone, Copy, Debug)]
+pub struct Opening {
+    pub code: Code,
+    pub salt: [u8; 32],
+}
+
+impl Opening {
+    pub fn new<R: Rng + ?Sized>(code: Code, rng: &mut R) -> Self {
+        Opening { code, salt: rng.gen() }
+    }
+
+    // sha256 over the colors followed by the salt, the same bytes the circuits hash
+    pub fn commit(&self) -> Commitment {
+        let mut hasher = Sha256::new();
+        hasher.update(self.code.colors);
+        hasher.update(self.salt);
+        Commitment { hash: hasher.finalize().into() }
+    }
+
+    pub fn opens(&self, commitment: &Commitment) -> bool {
+        self.commit() == *commitment
+    }
+}
+
+impl fmt::Display for Commitment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.hash.iter().try_for_each(|b| write!(f, "{:02x}", b))
+    }
+}
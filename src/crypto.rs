@@ -1,23 +1,31 @@
 use ark_crypto_primitives::crh::sha256::constraints::{DigestVar, Sha256Gadget};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::boolean::Boolean;
+use ark_r1cs_std::eq::EqGadget;
 use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::fields::FieldVar;
+use ark_r1cs_std::select::CondSelectGadget;
 use ark_r1cs_std::uint8::UInt8;
-use ark_relations::r1cs::Result;
-use ark_r1cs_std::ToBytesGadget;
+use ark_r1cs_std::R1CSVar;
+use ark_relations::ns;
+use ark_relations::r1cs::{ConstraintSystemRef, Result};
+use ark_r1cs_std::ToBitsGadget;
 use ark_bls12_381::Fr;
 
 type CircuitField = Fr;
 
 pub const COLOR_NUMBER: usize = 8;
 pub const CODE_LENGTH: usize = 4;
+pub const HASH_CHUNK_BYTES: usize = 16;
+pub const HASH_CHUNKS: usize = 32 / HASH_CHUNK_BYTES;
 
-#[derive(Clone, Debug)]
-pub struct Code {
-    pub colors: [u8; CODE_LENGTH],
-}
-
+// witnesses the byte and checks its bits add up to the whole value, so anything
+// outside 0..256 is rejected instead of being truncated to its lowest byte
 pub fn cast_fp_var_to_uint8(var: &FpVar<CircuitField>) -> Result<UInt8<CircuitField>> {
-    let bytes = FpVar::to_bytes(var)?;
-    Ok(bytes[0].clone())
+    let byte = UInt8::new_witness(var.cs(), || Ok(var.value()?.into_bigint().as_ref()[0] as u8))?;
+    Boolean::le_bits_to_fp_var(&byte.to_bits_le()?)?.enforce_equal(var)?;
+    Ok(byte)
 }
 
 pub fn compute_hash(
@@ -28,9 +36,165 @@ pub fn compute_hash(
 
     code_place_vars.iter().for_each(|col| {
         hash_gadget
-            .update(&[cast_fp_var_to_uint8(&col).unwrap()])
+            .update(&[cast_fp_var_to_uint8(col).unwrap()])
             .unwrap()
     });
     hash_gadget.update(salt_vars)?;
     hash_gadget.finalize()
-}
\ No newline at end of file
+}
+
+// the hash as little-endian 128-bit numbers, so it takes two public inputs instead of 256
+pub fn pack_hash(hash: &[u8; 32]) -> [CircuitField; HASH_CHUNKS] {
+    core::array::from_fn(|i| CircuitField::from_le_bytes_mod_order(&hash[i * HASH_CHUNK_BYTES..(i + 1) * HASH_CHUNK_BYTES]))
+}
+
+// rebuilds every packed chunk from the digest bits, matching `pack_hash`
+pub fn enforce_packed_hash(
+    digest_var: &DigestVar<CircuitField>,
+    hash_vars: &[FpVar<CircuitField>; HASH_CHUNKS],
+) -> Result<()> {
+    for (bytes, hash_var) in digest_var.0.chunks(HASH_CHUNK_BYTES).zip(hash_vars) {
+        let bits = bytes.to_bits_le()?;
+        Boolean::le_bits_to_fp_var(&bits)?.enforce_equal(hash_var)?;
+    }
+    Ok(())
+}
+
+// one boolean per color, exactly one of them set, and the color is their weighted sum;
+// this also pins the color to 0..COLOR_NUMBER
+pub fn one_hot(
+    cs: ConstraintSystemRef<CircuitField>,
+    col: &FpVar<CircuitField>,
+) -> Result<[Boolean<CircuitField>; COLOR_NUMBER]> {
+    one_hot_with(cs, col, |c| Ok(col.value()? == CircuitField::from(c as u8)))
+}
+
+// with the prover's bits left to the caller, so tests can try those of a cheating one
+fn one_hot_with(
+    cs: ConstraintSystemRef<CircuitField>,
+    col: &FpVar<CircuitField>,
+    bit: impl Fn(usize) -> Result<bool>,
+) -> Result<[Boolean<CircuitField>; COLOR_NUMBER]> {
+    let bits: [Boolean<CircuitField>; COLOR_NUMBER] = core::array::from_fn(|c| {
+        Boolean::new_witness(ns!(cs, "one_hot"), || bit(c)).unwrap()
+    });
+    let mut count = FpVar::zero();
+    let mut value = FpVar::zero();
+    for (c, bit) in bits.iter().enumerate() {
+        count += FpVar::from(bit.clone());
+        value += FpVar::from(bit.clone()) * CircuitField::from(c as u8);
+    }
+    count.enforce_equal(&FpVar::one())?;
+    value.enforce_equal(col)?;
+    Ok(bits)
+}
+
+// minimum of two counts in 0..=CODE_LENGTH. the prover picks the smaller one and
+// shows the difference fits in a few bits, which a wrong pick (a negative difference) cannot
+pub fn min_count(
+    cs: ConstraintSystemRef<CircuitField>,
+    a: &FpVar<CircuitField>,
+    b: &FpVar<CircuitField>,
+) -> Result<FpVar<CircuitField>> {
+    min_count_with(cs, a, b, || Ok(a.value()? <= b.value()?), |diff, i| Ok(diff.value()?.into_bigint().get_bit(i)))
+}
+
+// with the prover's pick and the difference's bits left to the caller, as for `one_hot_with`
+fn min_count_with(
+    cs: ConstraintSystemRef<CircuitField>,
+    a: &FpVar<CircuitField>,
+    b: &FpVar<CircuitField>,
+    pick_a: impl FnOnce() -> Result<bool>,
+    diff_bit: impl Fn(&FpVar<CircuitField>, usize) -> Result<bool>,
+) -> Result<FpVar<CircuitField>> {
+    let a_smaller = Boolean::new_witness(ns!(cs, "a_smaller"), pick_a)?;
+    let min = FpVar::conditionally_select(&a_smaller, a, b)?;
+    let diff = a + b - &min - &min;
+    let bits = (usize::BITS - CODE_LENGTH.leading_zeros()) as usize;
+    let mut packed = FpVar::zero();
+    for i in 0..bits {
+        let bit = Boolean::new_witness(ns!(cs, "diff_bit"), || diff_bit(&diff, i))?;
+        packed += FpVar::from(bit) * CircuitField::from(1u64 << i);
+    }
+    packed.enforce_equal(&diff)?;
+    Ok(min)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_relations::r1cs::ConstraintSystem;
+    use super::*;
+
+    fn cast_satisfies(value: u64) -> bool {
+        let cs = ConstraintSystem::<CircuitField>::new_ref();
+        let var = FpVar::new_witness(cs.clone(), || Ok(CircuitField::from(value))).unwrap();
+        cast_fp_var_to_uint8(&var).unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
+    #[test]
+    fn bytes_cast() {
+        assert!(cast_satisfies(0));
+        assert!(cast_satisfies(255));
+    }
+
+    // 256 and 257 share their lowest byte with 0 and 1
+    #[test]
+    fn values_past_a_byte_do_not_cast() {
+        assert!(!cast_satisfies(256));
+        assert!(!cast_satisfies(257));
+    }
+
+    fn witness(cs: &ConstraintSystemRef<CircuitField>, value: u64) -> FpVar<CircuitField> {
+        FpVar::new_witness(cs.clone(), || Ok(CircuitField::from(value))).unwrap()
+    }
+
+    #[test]
+    fn colors_are_one_hot() {
+        for col in 0..COLOR_NUMBER as u64 {
+            let cs = ConstraintSystem::<CircuitField>::new_ref();
+            let bits = one_hot(cs.clone(), &witness(&cs, col)).unwrap();
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(bits.iter().position(|bit| bit.value().unwrap()), Some(col as usize));
+        }
+    }
+
+    // whatever bits the prover makes up
+    #[test]
+    fn colors_past_the_last_are_not_one_hot() {
+        for col in [COLOR_NUMBER as u64, COLOR_NUMBER as u64 + 1, 255] {
+            for pattern in 0..1u32 << COLOR_NUMBER {
+                let cs = ConstraintSystem::<CircuitField>::new_ref();
+                let _ = one_hot_with(cs.clone(), &witness(&cs, col), |c| Ok(pattern >> c & 1 == 1)).unwrap();
+                assert!(!cs.is_satisfied().unwrap(), "color {} with bits {:08b}", col, pattern);
+            }
+        }
+    }
+
+    #[test]
+    fn min_counts() {
+        for a in 0..=CODE_LENGTH as u64 {
+            for b in 0..=CODE_LENGTH as u64 {
+                let cs = ConstraintSystem::<CircuitField>::new_ref();
+                let min = min_count(cs.clone(), &witness(&cs, a), &witness(&cs, b)).unwrap();
+                assert!(cs.is_satisfied().unwrap());
+                assert_eq!(min.value().unwrap(), CircuitField::from(a.min(b)));
+            }
+        }
+    }
+
+    // picking the larger count leaves a negative difference, which no bits add up to
+    #[test]
+    fn wrong_min_picks_do_not_satisfy() {
+        for a in 0..=CODE_LENGTH as u64 {
+            for b in (0..=CODE_LENGTH as u64).filter(|&b| b != a) {
+                // the three bits of any difference up to CODE_LENGTH
+                for pattern in 0..8u32 {
+                    let cs = ConstraintSystem::<CircuitField>::new_ref();
+                    let _ = min_count_with(cs.clone(), &witness(&cs, a), &witness(&cs, b), || Ok(a > b), |_, i| Ok(pattern >> i & 1 == 1)).unwrap();
+                    assert!(!cs.is_satisfied().unwrap(), "min of {} and {} with bits {:03b}", a, b, pattern);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use std::io;
+use std::path::PathBuf;
+use jiff::civil::Date;
+use jiff::tz::TimeZone;
+use jiff::Timestamp;
+use sha2::{Digest, Sha256};
+use crate::commitment::{Commitment, Opening};
+use crate::host::random_opening;
+use crate::seed::GameSeed;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{collections::BTreeMap, fmt, path::Path};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::inspector::{read_export, Statement};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::proof::Proof;
+
+// whoever runs the challenge sets their own; the default only keeps the mode playable,
+// as anyone with the binary can work out its codes
+const SECRET_VAR: &str = "ZK_MASTERMIND_DAILY_SECRET";
+const DEFAULT_SECRET: &str = "zk-mastermind";
+
+pub fn secret() -> String {
+    std::env::var(SECRET_VAR).unwrap_or_else(|_| DEFAULT_SECRET.to_owned())
+}
+
+// the day changes at midnight UTC for everyone
+pub fn today() -> Date {
+    TimeZone::UTC.to_datetime(Timestamp::now()).date()
+}
+
+// one code per date, derived from the secret, so every player of the day faces the same commitment.
+// The proofs stay fresh, only the code and the salt follow the date
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Daily {
+    pub date: Date,
+    pub seed: GameSeed,
+}
+
+impl Daily {
+    pub fn new(secret: &str, date: Date) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(secret.as_bytes());
+        hasher.update([0]);
+        hasher.update(date.to_string().as_bytes());
+        let hash: [u8; 32] = hasher.finalize().into();
+        let seed = u64::from_le_bytes(hash[..8].try_into().unwrap());
+        Daily { date, seed: GameSeed { seed, reproducible: false } }
+    }
+
+    // only for whoever holds the secret, and for everyone once the day is over
+    pub fn opening(&self) -> Opening {
+        random_opening(self.seed)
+    }
+
+    pub fn commitment(&self) -> Commitment {
+        self.opening().commit()
+    }
+
+    // the first line of every transcript of the day, the rest being the game's proofs as exported
+    pub fn header(&self) -> String {
+        format!("daily {} commitment {}", self.date, self.commitment())
+    }
+
+    pub fn save_transcript(&self, body: &str) -> io::Result<PathBuf> {
+        let path = PathBuf::from(format!("zk-mastermind-daily-{}.txt", self.date));
+        std::fs::write(&path, format!("{}\n{}", self.header(), body))?;
+        Ok(path)
+    }
+}
+
+// what the transcripts of one day add up to; only --verify-daily reads transcripts, which the
+// browser build lacks
+#[cfg(not(target_arch = "wasm32"))]
+pub struct DayReport {
+    pub daily: Daily,
+    pub matching: Vec<PathBuf>,
+    // transcripts of a host committed to something else
+    pub mismatched: Vec<PathBuf>,
+    // transcripts whose proofs do not verify, or without a commitment at all
+    pub unverified: Vec<PathBuf>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl fmt::Display for DayReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the code stays secret while the day's challenge is on
+        if self.daily.date < today() {
+            let opening = self.daily.opening();
+            writeln!(f, "{}: commitment {} opens to {} with salt {}",
+                self.daily.date, self.daily.commitment(), opening.code, crate::inspector::hex(&opening.salt))?;
+        } else {
+            writeln!(f, "{}: commitment {}, its code is revealed once the day is over", self.daily.date, self.daily.commitment())?;
+        }
+        if self.mismatched.is_empty() && self.unverified.is_empty() {
+            return write!(f, "  all {} transcripts faced this code", self.matching.len());
+        }
+        write!(f, "  {} transcripts faced this code", self.matching.len())?;
+        if !self.mismatched.is_empty() {
+            write!(f, "\n  {} did NOT:", self.mismatched.len())?;
+            self.mismatched.iter().try_for_each(|path| write!(f, "\n    {}", path.display()))?;
+        }
+        if !self.unverified.is_empty() {
+            write!(f, "\n  {} do not verify:", self.unverified.len())?;
+            self.unverified.iter().try_for_each(|path| write!(f, "\n    {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+type Proofs = Vec<(Statement, Proof)>;
+
+// only the date is taken from the header, the commitment that counts is the one the host proved.
+// Without a date the file fits no day; proofs which do not read are None, and counted as not verifying
+#[cfg(not(target_arch = "wasm32"))]
+fn read_transcript(path: &Path) -> Result<(Date, Option<Proofs>), String> {
+    let text = std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    let (header, body) = text.split_once('\n').unwrap_or((&text, ""));
+    let words: Vec<&str> = header.split_whitespace().collect();
+    let ["daily", date, "commitment", _] = words[..] else {
+        return Err(format!("{}: not a daily transcript", path.display()));
+    };
+    let date = date.parse().map_err(|err| format!("{}: {}", path.display(), err))?;
+    Ok((date, read_export(body).ok()))
+}
+
+// checks every transcript against the commitment of its day and verifies its proofs again, so players
+// can see afterwards that they all faced the same code. The proofs are checked against this process's
+// keys only, which are to be the day's from --keys: a player could make up proofs for keys of their own.
+// Files which are no daily transcript at all come back apart, as why they were skipped
+#[cfg(not(target_arch = "wasm32"))]
+pub fn verify_transcripts(secret: &str, paths: &[PathBuf]) -> (Vec<DayReport>, Vec<String>) {
+    let mut days: BTreeMap<Date, DayReport> = BTreeMap::new();
+    let mut skipped = Vec::new();
+    for path in paths {
+        let (date, proofs) = match read_transcript(path) {
+            Ok(read) => read,
+            Err(err) => {
+                skipped.push(err);
+                continue;
+            }
+        };
+        let report = days.entry(date).or_insert_with(|| DayReport {
+            daily: Daily::new(secret, date),
+            matching: Vec::new(),
+            mismatched: Vec::new(),
+            unverified: Vec::new(),
+        });
+        let Some(proofs) = proofs else {
+            report.unverified.push(path.clone());
+            continue;
+        };
+        let commitments: Vec<Commitment> = proofs.iter().map(|(statement, _)| match *statement {
+            Statement::Commitment(commitment) | Statement::Guess { commitment, .. } => commitment,
+        }).collect();
+        let committed = proofs.iter().any(|(statement, _)| matches!(statement, Statement::Commitment(_)));
+        if !committed || !proofs.iter().all(|(statement, proof)| statement.verify(proof)) {
+            report.unverified.push(path.clone());
+        } else if commitments.iter().all(|commitment| *commitment == report.daily.commitment()) {
+            report.matching.push(path.clone());
+        } else {
+            report.mismatched.push(path.clone());
+        }
+    }
+    (days.into_values().collect(), skipped)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use rand::thread_rng;
+    use crate::inspector::Inspector;
+    use crate::proof::prove;
+    use crate::seed::GameSeed;
+    use super::*;
+
+    fn transcript(dir: &Path, name: &str, header: &str, body: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, format!("{}\n{}", header, body)).unwrap();
+        path
+    }
+
+    #[test]
+    fn transcripts_are_sorted_by_what_they_faced() {
+        let daily = Daily::new("test", jiff::civil::date(2026, 1, 1));
+        let other = random_opening(GameSeed::reproducible(1));
+        let proof = prove(&daily.opening(), &mut thread_rng()).unwrap();
+        let other_proof = prove(&other, &mut thread_rng()).unwrap();
+
+        let export = |statement, proof| {
+            let mut inspector = Inspector::default();
+            inspector.record(statement, proof);
+            inspector.export()
+        };
+        let dir = std::env::temp_dir().join(format!("zk-mastermind-daily-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let header = daily.header();
+        let matching = transcript(&dir, "matching", &header, &export(Statement::Commitment(daily.commitment()), proof));
+        let mismatched = transcript(&dir, "mismatched", &header, &export(Statement::Commitment(other.commit()), Proof::from_bytes(&other_proof.to_bytes()).unwrap()));
+        // the other code's proof claimed for the day's commitment
+        let forged = transcript(&dir, "forged", &header, &export(Statement::Commitment(daily.commitment()), other_proof));
+        let uncommitted = transcript(&dir, "uncommitted", &header, "result: broken in 0 guesses\n");
+        let garbled = transcript(&dir, "garbled", &header, &format!("commitment {}\n  proof 00\n", daily.commitment()));
+        let other_day = transcript(&dir, "other-day", &Daily::new("test", jiff::civil::date(2026, 1, 2)).header(), "");
+        let not_daily = transcript(&dir, "not-daily", "key commitment 00", "");
+        let missing = dir.join("missing");
+
+        let paths = [matching.clone(), mismatched.clone(), forged.clone(), uncommitted.clone(), garbled.clone(), other_day, not_daily, missing];
+        let (reports, skipped) = verify_transcripts("test", &paths);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].daily, daily);
+        assert_eq!(reports[0].matching, [matching]);
+        assert_eq!(reports[0].mismatched, [mismatched]);
+        assert_eq!(reports[0].unverified, [forged, uncommitted, garbled]);
+        assert_eq!(reports[1].daily.date, jiff::civil::date(2026, 1, 2));
+        assert_eq!(reports[1].unverified.len(), 1);
+        assert_eq!(skipped.len(), 2);
+        assert!(skipped[0].contains("not a daily transcript"));
+    }
+}
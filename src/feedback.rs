@@ -0,0 +1,134 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::crypto::CODE_LENGTH;
+
+// black pegs count colors in the right place, white pegs the remaining color matches
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Feedback {
+    pub black: u8,
+    pub white: u8,
+}
+
+// answers have dense indices below this, for counting them in plain arrays
+pub const ANSWER_COUNT: usize = (CODE_LENGTH + 1) * (CODE_LENGTH + 1);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FeedbackError {
+    TooManyPegs(u8, u8),
+    Impossible(u8, u8),
+    CommonBelowCorrect(u8, u8),
+    Malformed(String),
+}
+
+impl fmt::Display for FeedbackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeedbackError::TooManyPegs(black, white) => write!(f, "{} black and {} white pegs do not fit in a row of {}", black, white, CODE_LENGTH),
+            FeedbackError::Impossible(black, white) => write!(f, "{} black and {} white pegs can never happen", black, white),
+            FeedbackError::CommonBelowCorrect(correct, common) => write!(f, "{} common colors cannot include {} correct ones", common, correct),
+            FeedbackError::Malformed(text) => write!(f, "not an answer like 1B2W: {:?}", text),
+        }
+    }
+}
+
+impl std::error::Error for FeedbackError {}
+
+impl Feedback {
+    pub fn new(black: u8, white: u8) -> Result<Self, FeedbackError> {
+        let length = CODE_LENGTH as u8;
+        if black as usize + white as usize > CODE_LENGTH {
+            return Err(FeedbackError::TooManyPegs(black, white));
+        }
+        // all but one in place and the last one misplaced would need a free spot to swap with
+        if black + 1 == length && white == 1 {
+            return Err(FeedbackError::Impossible(black, white));
+        }
+        Ok(Feedback { black, white })
+    }
+
+    // the circuit's public inputs count color matches including the exact ones
+    pub fn from_circuit(correct: u8, common: u8) -> Result<Self, FeedbackError> {
+        if common < correct {
+            return Err(FeedbackError::CommonBelowCorrect(correct, common));
+        }
+        Self::new(correct, common - correct)
+    }
+
+    pub fn correct(&self) -> u8 {
+        self.black
+    }
+
+    pub fn common(&self) -> u8 {
+        self.black + self.white
+    }
+
+    pub fn is_win(&self) -> bool {
+        self.black as usize == CODE_LENGTH
+    }
+
+    pub fn index(&self) -> usize {
+        self.black as usize * (CODE_LENGTH + 1) + self.white as usize
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Feedback { black: (index / (CODE_LENGTH + 1)) as u8, white: (index % (CODE_LENGTH + 1)) as u8 }
+    }
+}
+
+impl fmt::Display for Feedback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}B{}W", self.black, self.white)
+    }
+}
+
+// as displayed, e.g. "1B2W"
+impl FromStr for Feedback {
+    type Err = FeedbackError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let malformed = || FeedbackError::Malformed(s.to_owned());
+        let (black, white) = s.strip_suffix('W').and_then(|s| s.split_once('B')).ok_or_else(malformed)?;
+        Feedback::new(black.parse().map_err(|_| malformed())?, white.parse().map_err(|_| malformed())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+    use super::*;
+
+    #[test]
+    fn impossible_answers_are_rejected() {
+        assert_eq!(Feedback::new(3, 1), Err(FeedbackError::Impossible(3, 1)));
+        assert_eq!(Feedback::new(2, 3), Err(FeedbackError::TooManyPegs(2, 3)));
+        assert_eq!(Feedback::new(5, 0), Err(FeedbackError::TooManyPegs(5, 0)));
+        // large enough to overflow a u8 sum
+        assert_eq!(Feedback::new(200, 100), Err(FeedbackError::TooManyPegs(200, 100)));
+        assert_eq!(Feedback::new(2, 2), Ok(Feedback { black: 2, white: 2 }));
+        assert_eq!(Feedback::new(4, 0), Ok(Feedback { black: 4, white: 0 }));
+    }
+
+    #[test]
+    fn circuit_counts_include_the_correct_colors() {
+        assert_eq!(Feedback::from_circuit(1, 3), Ok(Feedback { black: 1, white: 2 }));
+        assert_eq!(Feedback::from_circuit(2, 1), Err(FeedbackError::CommonBelowCorrect(2, 1)));
+        let answer = Feedback::new(1, 2).unwrap();
+        assert_eq!(Feedback::from_circuit(answer.correct(), answer.common()), Ok(answer));
+    }
+
+    #[test]
+    fn answers_read_back_as_displayed() {
+        let answer = Feedback::new(1, 2).unwrap();
+        assert_eq!(answer.to_string(), "1B2W");
+        assert_eq!("1B2W".parse(), Ok(answer));
+        assert_eq!("3B1W".parse::<Feedback>(), Err(FeedbackError::Impossible(3, 1)));
+        for text in ["", "1B2", "B2W", "1b2w", "1W2B", "x"] {
+            assert_eq!(text.parse::<Feedback>(), Err(FeedbackError::Malformed(text.to_owned())));
+        }
+        for index in 0..ANSWER_COUNT {
+            let answer = Feedback::from_index(index);
+            assert_eq!(answer.index(), index);
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::time::Duration;
+use web_time::Instant;
+use crate::code::Code;
+use crate::commitment::{Commitment, Opening};
+use crate::feedback::Feedback;
+use crate::host::{Host, HostError};
+use crate::proof::Proof;
+
+// one message per `Host` method
+#[derive(Clone, Copy, Debug)]
+pub enum Request {
+    Commit,
+    Guess(Code),
+    Surrender,
+}
+
+pub enum Response {
+    Committed(Commitment, Proof),
+    Answered(Feedback, Proof),
+    Surrendered(Option<Opening>),
+}
+
+// what the host's side saw of one exchange, without the proofs
+#[derive(Clone, Copy, Debug)]
+pub enum Served {
+    Committed(Commitment),
+    Answered(Code, Feedback),
+    Surrendered,
+    Failed(Request),
+}
+
+fn serve(host: &mut dyn Host, request: Request) -> Result<Response, HostError> {
+    match request {
+        Request::Commit => host.get_hash_with_proof().map(|(commitment, proof)| Response::Committed(commitment, proof)),
+        Request::Guess(guess) => host.guess(guess).map(|(answer, proof)| Response::Answered(answer, proof)),
+        Request::Surrender => Ok(Response::Surrendered(host.surrender())),
+    }
+}
+
+// a host running on its own thread, or taking turns with the others in the browser; requests go
+// one at a time and the answer is picked up by polling, so a slow host never blocks the caller
+pub struct HostHandle {
+    requests: Sender<(u64, Request)>,
+    responses: Receiver<(u64, Result<Response, HostError>)>,
+    pending: Option<(u64, Request, Instant)>,
+    next_id: u64,
+    timeout: Option<Duration>,
+}
+
+// the other end of a `HostHandle`, for a host which is set up after the handle, e.g. in another window
+pub struct HostEndpoint {
+    incoming: Receiver<(u64, Request)>,
+    outgoing: Sender<(u64, Result<Response, HostError>)>,
+}
+
+pub fn channel() -> (HostHandle, HostEndpoint) {
+    let (requests, incoming) = mpsc::channel();
+    let (outgoing, responses) = mpsc::channel();
+    let handle = HostHandle { requests, responses, pending: None, next_id: 0, timeout: None };
+    (handle, HostEndpoint { incoming, outgoing })
+}
+
+// a host together with the endpoint it answers on
+struct Server {
+    endpoint: HostEndpoint,
+    host: Box<dyn Host>,
+    observer: Option<Sender<Served>>,
+}
+
+impl Server {
+    // false once the handle is gone
+    fn answer(&mut self, id: u64, request: Request) -> bool {
+        let response = serve(self.host.as_mut(), request);
+        if let Some(observer) = &self.observer {
+            let served = match (&response, request) {
+                (Ok(Response::Committed(commitment, _)), _) => Served::Committed(*commitment),
+                (Ok(Response::Answered(answer, _)), Request::Guess(guess)) => Served::Answered(guess, *answer),
+                (Ok(Response::Surrendered(_)), _) => Served::Surrendered,
+                _ => Served::Failed(request),
+            };
+            observer.send(served).ok();
+        }
+        self.endpoint.outgoing.send((id, response)).is_ok()
+    }
+}
+
+// a page in the browser has a single thread, so there the hosts take turns on it: polling any handle
+// serves the requests waiting for all of them, and the frame stalls while they prove
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    static SERVERS: std::cell::RefCell<Vec<Server>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+#[cfg(target_arch = "wasm32")]
+fn serve_waiting() {
+    SERVERS.with_borrow_mut(|servers| servers.retain_mut(|server| loop {
+        match server.endpoint.incoming.try_recv() {
+            Ok((id, request)) => {
+                if !server.answer(id, request) {
+                    break false;
+                }
+            }
+            Err(TryRecvError::Empty) => break true,
+            Err(TryRecvError::Disconnected) => break false,
+        }
+    }));
+}
+
+impl HostEndpoint {
+    // serves on a thread of its own until the handle is dropped; requests sent before
+    // this wait in the channel. The observer, if any, hears about every exchange
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn serve(self, host: Box<dyn Host>, observer: Option<Sender<Served>>) {
+        let mut server = Server { endpoint: self, host, observer };
+        std::thread::spawn(move || {
+            while let Ok((id, request)) = server.endpoint.incoming.recv() {
+                if !server.answer(id, request) {
+                    break;
+                }
+            }
+        });
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn serve(self, host: Box<dyn Host>, observer: Option<Sender<Served>>) {
+        SERVERS.with_borrow_mut(|servers| servers.push(Server { endpoint: self, host, observer }));
+    }
+}
+
+impl HostHandle {
+    pub fn spawn(host: Box<dyn Host>) -> Self {
+        let (handle, endpoint) = channel();
+        endpoint.serve(host, None);
+        handle
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn pending(&self) -> Option<Request> {
+        self.pending.map(|(_, request, _)| request)
+    }
+
+    pub fn send(&mut self, request: Request) -> Result<(), HostError> {
+        if self.pending.is_some() {
+            return Err(HostError::ProtocolViolation("a request is still pending"));
+        }
+        self.requests.send((self.next_id, request)).map_err(|_| HostError::Disconnected)?;
+        self.pending = Some((self.next_id, request, Instant::now()));
+        self.next_id += 1;
+        Ok(())
+    }
+
+    // the answer to the pending request together with that request, once there is one
+    pub fn poll(&mut self) -> Option<(Request, Result<Response, HostError>)> {
+        let (id, request, sent) = self.pending?;
+        #[cfg(target_arch = "wasm32")]
+        serve_waiting();
+        loop {
+            match self.responses.try_recv() {
+                Ok((answered, response)) if answered == id => {
+                    self.pending = None;
+                    return Some((request, response));
+                }
+                Ok(_) => continue, // left over from a request which timed out
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.pending = None;
+                    return Some((request, Err(HostError::Disconnected)));
+                }
+            }
+        }
+        if self.timeout.is_some_and(|timeout| sent.elapsed() > timeout) {
+            self.pending = None;
+            return Some((request, Err(HostError::TimedOut)));
+        }
+        None
+    }
+}
+
+// the hosts answer on threads of their own here
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    // answers guesses with an error and surrenders once the gate opens, so nothing needs proving
+    struct Stub {
+        gate: Receiver<()>,
+    }
+
+    impl Host for Stub {
+        fn get_hash_with_proof(&mut self) -> Result<(Commitment, Proof), HostError> {
+            Err(HostError::ProtocolViolation("the stub does not commit"))
+        }
+
+        fn guess(&mut self, guess: Code) -> Result<(Feedback, Proof), HostError> {
+            Err(HostError::InvalidGuess(guess))
+        }
+
+        fn surrender(&mut self) -> Option<Opening> {
+            self.gate.recv().ok()?;
+            Some(Opening { code: "abcd".parse().unwrap(), salt: [0; 32] })
+        }
+    }
+
+    fn stub() -> (HostHandle, Sender<()>) {
+        let (gate, waiting) = mpsc::channel();
+        (HostHandle::spawn(Box::new(Stub { gate: waiting })), gate)
+    }
+
+    fn wait(handle: &mut HostHandle) -> (Request, Result<Response, HostError>) {
+        for _ in 0..1000 {
+            if let Some(answer) = handle.poll() {
+                return answer;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        panic!("no answer");
+    }
+
+    #[test]
+    fn answers_are_polled() {
+        let (mut handle, gate) = stub();
+        assert!(handle.poll().is_none());
+        handle.send(Request::Surrender).unwrap();
+        assert!(matches!(handle.pending(), Some(Request::Surrender)));
+        assert!(handle.send(Request::Commit).is_err());
+        gate.send(()).unwrap();
+        assert!(matches!(wait(&mut handle), (Request::Surrender, Ok(Response::Surrendered(Some(_))))));
+        assert!(handle.pending().is_none());
+    }
+
+    #[test]
+    fn slow_hosts_time_out() {
+        let (handle, _gate) = stub();
+        let mut handle = handle.with_timeout(Duration::from_millis(20));
+        handle.send(Request::Surrender).unwrap();
+        assert!(matches!(wait(&mut handle), (Request::Surrender, Err(HostError::TimedOut))));
+        assert!(handle.pending().is_none());
+    }
+
+    // the late answer to the request which timed out is not taken for the next one's
+    #[test]
+    fn late_answers_are_dropped() {
+        let (handle, gate) = stub();
+        let mut handle = handle.with_timeout(Duration::from_millis(20));
+        handle.send(Request::Surrender).unwrap();
+        assert!(matches!(wait(&mut handle), (_, Err(HostError::TimedOut))));
+        gate.send(()).unwrap();
+        let guess: Code = "aabb".parse().unwrap();
+        handle.send(Request::Guess(guess)).unwrap();
+        assert!(matches!(wait(&mut handle), (Request::Guess(_), Err(HostError::InvalidGuess(answered))) if answered == guess));
+    }
+
+    #[test]
+    fn gone_hosts_disconnect() {
+        let (mut handle, endpoint) = channel();
+        handle.send(Request::Commit).unwrap();
+        drop(endpoint);
+        assert!(matches!(wait(&mut handle), (Request::Commit, Err(HostError::Disconnected))));
+        assert!(matches!(handle.send(Request::Commit), Err(HostError::Disconnected)));
+    }
+}
@@ -1,148 +1,316 @@
-use itertools::Itertools;
-use rand::Rng;
-use std::collections::{HashMap, HashSet};
-use crate::crypto::Code;
-use crate::utils::{common, hash, same, string_to_code};
-use crate::proof::{prove, prove_guess, Proof};
-const CHARSET: &[u8] = b"abcdefgh";
-const SEQUENCE_LEN: usize = 4;
-
-pub trait Host {
-    fn new () -> Self;
-    fn get_hash_with_proof(&self) -> ([u8; 32], Proof);
-    fn guess(&mut self, sequence: String) -> (usize, usize, Proof);
-    fn surrender(&mut self) -> String;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::fmt;
+use crate::code::Code;
+use crate::commitment::{Commitment, Opening};
+use crate::solver::guesses_per_secret;
+use crate::feedback::Feedback;
+use crate::candidates::{answer, Candidates};
+use crate::feedback::ANSWER_COUNT;
+use crate::proof::{prove, prove_guess, Proof, ProvingError};
+use crate::seed::GameSeed;
+
+#[derive(Debug)]
+pub enum HostError {
+    InvalidGuess(Code),
+    ProvingFailed(ProvingError),
+    GameOver,
+    ProtocolViolation(&'static str),
+    TimedOut,
+    Disconnected,
+}
+
+impl fmt::Display for HostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HostError::InvalidGuess(guess) => write!(f, "invalid guess {:?}", guess.colors),
+            HostError::ProvingFailed(err) => write!(f, "proving failed: {}", err),
+            HostError::GameOver => write!(f, "the game is already over"),
+            HostError::ProtocolViolation(what) => write!(f, "protocol violation: {}", what),
+            HostError::TimedOut => write!(f, "the host did not answer in time"),
+            HostError::Disconnected => write!(f, "the host is gone"),
+        }
+    }
+}
+
+impl std::error::Error for HostError {}
+
+impl From<ProvingError> for HostError {
+    fn from(err: ProvingError) -> Self {
+        HostError::ProvingFailed(err)
+    }
+}
+
+// hosts are constructed through their own `new`, so the trait stays usable as `Box<dyn Host>`;
+// Send so that a `HostHandle` can run them on their own thread
+pub trait Host: Send {
+    fn get_hash_with_proof(&mut self) -> Result<(Commitment, Proof), HostError>;
+    fn guess(&mut self, guess: Code) -> Result<(Feedback, Proof), HostError>;
+    // None if the host refuses to reveal its code, the opening lets the player check it against the commitment
+    fn surrender(&mut self) -> Option<Opening>;
+}
+
+fn check_guess(guess: &Code) -> Result<(), HostError> {
+    if guess.is_valid() {
+        Ok(())
+    } else {
+        Err(HostError::InvalidGuess(*guess))
+    }
+}
+
+// a random code with its salt, both decided by the seed
+pub fn random_opening(seed: GameSeed) -> Opening {
+    let mut rng = seed.secret_rng();
+    let code = Code::random(&mut rng);
+    Opening::new(code, &mut rng)
 }
 
 pub struct HonestHost {
-    sequence: String,
-    salt: [u8; 32],
-    hash: [u8; 32],
+    opening: Opening,
+    committed: bool,
+    solved: bool,
+    rng: StdRng,
 }
 
-impl Host for HonestHost {
-    fn new () -> Self {
-        let mut rng = rand::thread_rng();
-        let random_seq : String = (0..SEQUENCE_LEN)
-        .map(|_| {
-            let idx = rng.gen_range(0..CHARSET.len());
-            CHARSET[idx] as char
-        })
-        .collect();
-        let code = string_to_code(random_seq.clone());
-        let (hash, salt) = hash(code);
+impl HonestHost {
+    pub fn new (seed: GameSeed) -> Self {
+        Self::with_opening(random_opening(seed), seed)
+    }
+
+    // still honest, but commits to a code on which the solver needs the most guesses
+    pub fn hardest(seed: GameSeed) -> Self {
+        let guesses = guesses_per_secret();
+        let most = guesses.values().copied().max().unwrap();
+        let mut hardest: Vec<_> = guesses.into_iter().filter(|(_, n)| *n == most).map(|(code, _)| code).collect();
+        // in a fixed order, so the seed alone decides the code
+        hardest.sort_by_key(Code::index);
+        let mut rng = seed.secret_rng();
+        let code = *hardest.choose(&mut rng).unwrap();
+        Self::with_opening(Opening::new(code, &mut rng), seed)
+    }
+
+    // a person's code; its salt never follows the seed, as the seed is shown once the game is over
+    // and the code could then be found from the commitment by trying them all
+    pub fn with_code(code: Code, seed: GameSeed) -> Self {
+        Self::with_opening(Opening::new(code, &mut rand::thread_rng()), seed)
+    }
+
+    fn with_opening(opening: Opening, seed: GameSeed) -> Self {
         HonestHost {
-            sequence: random_seq,
-            salt,
-            hash,
+            opening,
+            committed: false,
+            solved: false,
+            rng: seed.proving_rng(),
         }
     }
-    fn guess(&mut self, sequence: String)-> (usize, usize, Proof) {
-        let (correct, common) = (same(self.sequence.clone(), sequence.clone()), common(self.sequence.clone(), sequence.clone()));
-        let proof = prove_guess(string_to_code(self.sequence.clone()), string_to_code(sequence.clone()), self.salt, self.hash, correct as u8, common as u8);
-        return (correct, common, proof)
+}
+
+impl Host for HonestHost {
+    fn guess(&mut self, guess: Code)-> Result<(Feedback, Proof), HostError> {
+        if !self.committed {
+            return Err(HostError::ProtocolViolation("guess before the code was committed"));
+        }
+        if self.solved {
+            return Err(HostError::GameOver);
+        }
+        check_guess(&guess)?;
+        let answer = answer(&self.opening.code, &guess);
+        let proof = prove_guess(&self.opening, guess, answer, &mut self.rng)?;
+        self.solved = answer.is_win();
+        Ok((answer, proof))
     }
-    
-    fn get_hash_with_proof(&self) -> ([u8; 32], Proof) {
-        let code = string_to_code(self.sequence.clone());
-        println!("{}", self.sequence);
-        println!("{:?}", code);
-        let proof = prove(code, self.salt, self.hash);
-        (self.hash, proof)
+
+    fn get_hash_with_proof(&mut self) -> Result<(Commitment, Proof), HostError> {
+        let proof = prove(&self.opening, &mut self.rng)?;
+        self.committed = true;
+        Ok((self.opening.commit(), proof))
     }
 
-    fn surrender(&mut self) -> String {
-        self.sequence.clone()
+    fn surrender(&mut self) -> Option<Opening> {
+        Some(self.opening)
     }
 }
 
-pub struct EvilHost {}
+const EVIL_CODE: Code = Code { colors: [1, 1, 1, 1] };
+
+pub struct EvilHost {
+    opening: Opening,
+    rng: StdRng,
+}
 
-impl Host for EvilHost{ // host which always answers with (0, 0)
-    fn new () -> Self {
-        EvilHost { }
+impl EvilHost { // host which always answers with no pegs
+    pub fn new (seed: GameSeed) -> Self {
+        EvilHost { opening: Opening::new(EVIL_CODE, &mut seed.secret_rng()), rng: seed.proving_rng() }
     }
+}
 
-    fn guess(&mut self, sequence: String)-> (usize, usize, Proof) {
-        let code = Code{colors:[1, 1, 1, 1]};
-        let (hash, salt) = hash(code.clone());
-        let proof = prove_guess(code, string_to_code(sequence), salt, hash, 0, 0);
-        return (0, 0, proof);
+impl Host for EvilHost {
+    fn guess(&mut self, guess: Code)-> Result<(Feedback, Proof), HostError> {
+        check_guess(&guess)?;
+        // the proof is for the true answer, so it fails to verify against the claimed empty one
+        let answer = answer(&EVIL_CODE, &guess);
+        let proof = prove_guess(&self.opening, guess, answer, &mut self.rng)?;
+        Ok((Feedback { black: 0, white: 0 }, proof))
     }
-    
-    fn get_hash_with_proof(&self) -> ([u8; 32], Proof) {
-        let code = Code{colors:[1, 1, 1, 1]};
-        let (hash, salt) = hash(code.clone());
-        let proof = prove(code, salt, hash);
-        (hash, proof)
+
+    fn get_hash_with_proof(&mut self) -> Result<(Commitment, Proof), HostError> {
+        let proof = prove(&self.opening, &mut self.rng)?;
+        Ok((self.opening.commit(), proof))
     }
 
-    fn surrender(&mut self) -> String {
-        "xxxx".to_owned()
+    fn surrender(&mut self) -> Option<Opening> {
+        None
     }
 }
 
+// ties go to the answer with fewer pegs
+fn worst_case_answer(counts: &[usize; ANSWER_COUNT]) -> Feedback {
+    (0..ANSWER_COUNT).map(Feedback::from_index)
+        .filter(|answer| counts[answer.index()] > 0)
+        .max_by_key(|answer| (counts[answer.index()], std::cmp::Reverse(answer.correct()), std::cmp::Reverse(answer.common())))
+        .unwrap()
+}
+
 pub struct CheatingHost { //host which always gives worst case answer for player
-    possible_sequences: HashSet<String>,
-    worst_case_sequences: HashMap<(usize, usize), i32>
-}
-
-impl Host for CheatingHost{
-    fn new () -> Self {
-        let characters = vec!["a", "b", "c", "d", "e", "f", "g", "h"];
-        let pairs : Vec<_> = characters.iter()
-        .cartesian_product(characters.iter())
-        .map(|(&a, &b)| a.to_owned() + b)
-        .collect();
-        let quadruples : Vec<_> = pairs.iter()
-        .cartesian_product(pairs.iter())
-        .map(|(a, b)| a.to_owned() + b)
-        .collect();
-        let s: HashSet<String, _> = HashSet::from_iter(quadruples);
-        let mut m: HashMap<(usize, usize), i32> = HashMap::new();
-        for i in 0usize..=4 {
-            for j in i..=4 {
-                m.insert((i, j), 0);
-            }
-        }
+    possible_codes: Candidates,
+    // for the salts of its ever new openings
+    secrets: StdRng,
+    rng: StdRng,
+}
+
+impl CheatingHost {
+    pub fn new (seed: GameSeed) -> Self {
         CheatingHost {
-            possible_sequences: s,
-            worst_case_sequences: m,
+            possible_codes: Candidates::all(),
+            secrets: seed.secret_rng(),
+            rng: seed.proving_rng(),
         }
     }
+}
+
+impl Host for CheatingHost {
+    fn guess(&mut self, guess: Code)-> Result<(Feedback, Proof), HostError> {
+        check_guess(&guess)?;
+        let ans = worst_case_answer(&self.possible_codes.answer_counts(&guess));
+        self.possible_codes.retain(&guess, ans);
+        let code = self.possible_codes.first()
+            .ok_or(HostError::ProtocolViolation("no code is consistent with the answers"))?;
+        // a fresh opening each time, so these proofs never match the first commitment
+        let opening = Opening::new(code, &mut self.secrets);
+        let proof = prove_guess(&opening, guess, ans, &mut self.rng)?;
+        Ok((ans, proof))
+    }
+
+    fn get_hash_with_proof(&mut self) -> Result<(Commitment, Proof), HostError> {
+        let code = self.possible_codes.first().unwrap();
+        let opening = Opening::new(code, &mut self.secrets);
+        let proof = prove(&opening, &mut self.rng)?;
+        Ok((opening.commit(), proof))
+    }
+
+    fn surrender(&mut self) -> Option<Opening> {
+        let code = self.possible_codes.first().unwrap();
+        Some(Opening::new(code, &mut self.secrets))
+    }
+}
+
+// "lazy commitment" adversary: commits to a real code and answers honestly for it,
+// until a worse answer for the player requires swapping the code behind the commitment.
+// every proof it sends is valid, but after the swap only for a different hash, which the verifier rejects
+pub struct LazyHost {
+    possible_codes: Candidates,
+    opening: Opening,
+    rng: StdRng,
+}
 
-    fn guess(&mut self, sequence: String)-> (usize, usize, Proof) {
-        let mut m = self.worst_case_sequences.clone();
-        for seq in self.possible_sequences.clone() {
-            let (same, common) = (same(seq.clone(), sequence.clone()), common(seq.clone(), sequence.clone()));
-            let old = m.get(&(same, common)).unwrap();
-            m.insert((same, common), old+1);
+impl LazyHost {
+    pub fn new (seed: GameSeed) -> Self {
+        LazyHost {
+            possible_codes: Candidates::all(),
+            opening: random_opening(seed),
+            rng: seed.proving_rng(),
         }
-        let ans = m.iter()
-            .max_by(|a, b| (a.1, - (a.0.0 as i32), - (a.0.1 as i32)).cmp(&(b.1, - (b.0.0 as i32), - (b.0.1 as i32))))
-            .unwrap().0;
-        for seq in self.possible_sequences.clone() {
-            let (same, common) = (same(seq.clone(), sequence.clone()), common(seq.clone(), sequence.clone()));
-            if (same, common) != *ans {
-                self.possible_sequences.remove(&seq);
-            }
+    }
+}
+
+impl Host for LazyHost {
+    fn guess(&mut self, guess: Code)-> Result<(Feedback, Proof), HostError> {
+        check_guess(&guess)?;
+        let counts = self.possible_codes.answer_counts(&guess);
+        let honest = answer(&self.opening.code, &guess);
+        let worst = worst_case_answer(&counts);
+        // keep the committed code whenever the honest answer is just as bad for the player
+        let ans = if counts[honest.index()] == counts[worst.index()] { honest } else { worst };
+        self.possible_codes.retain(&guess, ans);
+        if ans != honest {
+            // keeping the salt does not help, the commitment binds the code
+            self.opening.code = self.possible_codes.first()
+                .ok_or(HostError::ProtocolViolation("no code is consistent with the answers"))?;
         }
-        let seq = self.possible_sequences.iter().next().unwrap().clone();
-        let code = string_to_code(seq);
-        let (hash, salt) = hash(code.clone());
-        let proof = prove_guess(code, string_to_code(sequence), salt, hash, ans.0 as u8, ans.1 as u8);
-        return (ans.0, ans.1, proof);
+        let proof = prove_guess(&self.opening, guess, ans, &mut self.rng)?;
+        Ok((ans, proof))
     }
-    
-    fn get_hash_with_proof(&self) -> ([u8; 32], Proof) {
-        let seq = self.possible_sequences.iter().next().unwrap().clone();
-        let code = string_to_code(seq);
-        let (hash, salt) = hash(code.clone());
-        let proof = prove(code, salt, hash);
-        (hash, proof)
+
+    fn get_hash_with_proof(&mut self) -> Result<(Commitment, Proof), HostError> {
+        let proof = prove(&self.opening, &mut self.rng)?;
+        Ok((self.opening.commit(), proof))
     }
 
-    fn surrender(&mut self) -> String {
-        self.possible_sequences.iter().next().unwrap().clone()
+    fn surrender(&mut self) -> Option<Opening> {
+        Some(self.opening)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::proof::{verify, verify_guess};
+    use super::*;
+
+    // the code and the commitment follow the seed alone, whether or not it was given with --seed
+    #[test]
+    fn seeds_replay_codes_and_commitments() {
+        let seed = GameSeed::reproducible(48);
+        let reported = GameSeed { seed: 48, reproducible: false };
+        let openings: [fn(GameSeed) -> Opening; 4] = [
+            |seed| HonestHost::new(seed).opening,
+            |seed| HonestHost::hardest(seed).opening,
+            |seed| EvilHost::new(seed).opening,
+            |seed| LazyHost::new(seed).opening,
+        ];
+        for opening in openings {
+            let (first, again) = (opening(seed), opening(reported));
+            assert_eq!(first.code, again.code);
+            assert_eq!(first.salt, again.salt);
+            assert_eq!(first.commit(), again.commit());
+            assert_ne!(first.commit(), opening(GameSeed::reproducible(49)).commit());
+        }
+    }
+
+    #[test]
+    fn hardest_codes_need_the_most_guesses() {
+        let guesses = guesses_per_secret();
+        let most = guesses.values().copied().max().unwrap();
+        for seed in 0..4 {
+            let host = HonestHost::hardest(GameSeed::reproducible(seed));
+            assert_eq!(guesses[&host.opening.code], most);
+        }
+    }
+
+    // a seed whose code gives a better answer than the worst case, so the host has to swap
+    #[test]
+    fn swapped_codes_do_not_verify() {
+        let guess: Code = "aabb".parse().unwrap();
+        let worst = worst_case_answer(&Candidates::all().answer_counts(&guess));
+        let seed = (0..).map(GameSeed::reproducible).find(|&seed| answer(&random_opening(seed).code, &guess) != worst).unwrap();
+        let mut host = LazyHost::new(seed);
+        let (commitment, proof) = host.get_hash_with_proof().unwrap();
+        assert!(verify(&commitment, &proof));
+        let committed = host.opening.code;
+        let (ans, proof) = host.guess(guess).unwrap();
+        assert_eq!(ans, worst);
+        assert_ne!(host.opening.code, committed);
+        assert!(!verify_guess(guess, &commitment, ans, &proof));
+        // the proof itself is sound, for the commitment to the swapped code
+        assert!(verify_guess(guess, &host.opening.commit(), ans, &proof));
+    }
+}
@@ -0,0 +1,189 @@
+use std::time::Duration;
+use web_time::Instant;
+use eframe::egui;
+use crate::board::{self, BoardConfig};
+use crate::code::Code;
+use crate::commitment::Commitment;
+use crate::feedback::Feedback;
+use crate::proof::{code_key, guess_key, verifier_to_bytes, verify, verify_guess, Proof};
+
+// what a proof claims, i.e. its public inputs
+#[derive(Clone, Copy, Debug)]
+pub enum Statement {
+    Commitment(Commitment),
+    Guess { guess: Code, commitment: Commitment, answer: Feedback },
+}
+
+impl Statement {
+    pub fn verify(&self, proof: &Proof) -> bool {
+        match self {
+            Statement::Commitment(commitment) => verify(commitment, proof),
+            Statement::Guess { guess, commitment, answer } => verify_guess(*guess, commitment, *answer, proof),
+        }
+    }
+}
+
+pub struct ProofRecord {
+    pub statement: Statement,
+    pub proof: Proof,
+    pub verified: bool,
+    pub verifying_time: Duration,
+}
+
+impl ProofRecord {
+    fn check(&mut self) {
+        let start = Instant::now();
+        self.verified = self.statement.verify(&self.proof);
+        self.verifying_time = start.elapsed();
+    }
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn unhex(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 {
+        return None;
+    }
+    (0..text.len()).step_by(2).map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok()).collect()
+}
+
+// exports are only read back by --verify-daily, which the browser build lacks
+#[cfg(not(target_arch = "wasm32"))]
+fn read_commitment(text: &str) -> Result<Commitment, String> {
+    let hash = unhex(text).and_then(|bytes| bytes.try_into().ok()).ok_or_else(|| format!("not a commitment: {:?}", text))?;
+    Ok(Commitment { hash })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_guess(guess: &str, answer: &str, commitment: &str) -> Result<Statement, String> {
+    Ok(Statement::Guess {
+        guess: guess.parse().map_err(|err| format!("{}", err))?,
+        commitment: read_commitment(commitment)?,
+        answer: answer.parse().map_err(|err| format!("{}", err))?,
+    })
+}
+
+// every statement with its proof; lines other than statements and proofs, like the keys
+// or a game's result, are skipped
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_export(text: &str) -> Result<Vec<(Statement, Proof)>, String> {
+    let mut statement = None;
+    let mut proofs = Vec::new();
+    for line in text.lines() {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let read = match words[..] {
+            ["commitment", commitment, ..] => read_commitment(commitment).map(|commitment| statement = Some(Statement::Commitment(commitment))),
+            ["guess", guess, answer, "against", commitment, ..] => read_guess(guess, answer, commitment).map(|guess| statement = Some(guess)),
+            ["proof", proof] => {
+                let statement = statement.take().ok_or_else(|| "a proof without a statement".to_owned())?;
+                let bytes = unhex(proof).ok_or_else(|| "a proof is not hex".to_owned())?;
+                Proof::from_bytes(&bytes).map(|proof| proofs.push((statement, proof))).map_err(|err| format!("not a proof: {}", err))
+            }
+            _ => Ok(()),
+        };
+        read?;
+    }
+    Ok(proofs)
+}
+
+fn record_ui(ui: &mut egui::Ui, config: &BoardConfig, record: &mut ProofRecord) {
+    egui::Grid::new(ui.id().with("inputs")).num_columns(2).show(ui, |ui| {
+        match record.statement {
+            Statement::Commitment(commitment) => {
+                ui.label("hash");
+                ui.monospace(commitment.to_string());
+                ui.end_row();
+            }
+            Statement::Guess { guess, commitment, answer } => {
+                ui.label("guess");
+                ui.horizontal(|ui| {
+                    board::code_ui(ui, config, &guess);
+                    ui.monospace(format!("{:?}", guess.colors));
+                });
+                ui.end_row();
+                ui.label("hash");
+                ui.monospace(commitment.to_string());
+                ui.end_row();
+                ui.label("correct");
+                ui.monospace(answer.correct().to_string());
+                ui.end_row();
+                ui.label("common");
+                ui.monospace(answer.common().to_string());
+                ui.end_row();
+            }
+        }
+        ui.label("constraints");
+        ui.label(record.proof.constraints.to_string());
+        ui.end_row();
+        ui.label("proving");
+        ui.label(format!("{:.2?}", record.proof.proving_time));
+        ui.end_row();
+        ui.label("verifying");
+        ui.label(format!("{:.2?}", record.verifying_time));
+        ui.end_row();
+    });
+    let bytes = hex(&record.proof.to_bytes());
+    ui.label(format!("proof ({} bytes)", bytes.len() / 2));
+    ui.add(egui::Label::new(egui::RichText::new(&bytes).monospace().small()).wrap(true));
+    ui.horizontal(|ui| {
+        if ui.button("Re-verify").clicked() {
+            record.check();
+        }
+        if ui.button("Copy proof").clicked() {
+            ui.ctx().copy_text(bytes);
+        }
+    });
+}
+
+// every proof of a game with its public inputs, for players who want to see them checked
+#[derive(Default)]
+pub struct Inspector {
+    records: Vec<ProofRecord>,
+}
+
+impl Inspector {
+    // verifies the proof and keeps it; returns whether it verified
+    pub fn record(&mut self, statement: Statement, proof: Proof) -> bool {
+        let mut record = ProofRecord { statement, proof, verified: false, verifying_time: Duration::ZERO };
+        record.check();
+        let verified = record.verified;
+        self.records.push(record);
+        verified
+    }
+
+    // the verifying keys the proofs were checked against, then every statement with its proof, all in hex:
+    // enough for anyone to check the proofs again, as far as they trust that these were the keys
+    pub fn export(&self) -> String {
+        let mut text = format!("key commitment {}\n", hex(&verifier_to_bytes(&code_key().vk)));
+        text += &format!("key guess {}\n", hex(&verifier_to_bytes(&guess_key().vk)));
+        for record in &self.records {
+            match record.statement {
+                Statement::Commitment(commitment) => text += &format!("commitment {}", commitment),
+                Statement::Guess { guess, commitment, answer } => text += &format!("guess {} {} against {}", guess, answer, commitment),
+            }
+            text += if record.verified { " (verified)\n" } else { " (NOT verified)\n" };
+            text += &format!("  proof {}\n", hex(&record.proof.to_bytes()));
+        }
+        text
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, config: &BoardConfig) {
+        ui.heading("Proofs");
+        if self.records.is_empty() {
+            ui.label("no proofs yet");
+        }
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (i, record) in self.records.iter_mut().enumerate() {
+                let title = match record.statement {
+                    Statement::Commitment(_) => "Commitment".to_string(),
+                    Statement::Guess { guess, answer, .. } => format!("Guess {}: {} {}", i, guess, answer),
+                };
+                let (mark, color) = if record.verified { ("✔", egui::Color32::GREEN) } else { ("✘", egui::Color32::RED) };
+                let header = egui::RichText::new(format!("{} {}", mark, title)).color(color);
+                egui::CollapsingHeader::new(header).id_source(i).show(ui, |ui| record_ui(ui, config, record));
+            }
+        });
+    }
+}
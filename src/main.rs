@@ -1,188 +1,698 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 #![allow(rustdoc::missing_crate_level_docs)] // it's an example
 
-mod utils;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
+mod code;
+mod commitment;
 mod host;
 mod consts;
+mod feedback;
 mod proof;
 mod crypto;
 mod code_circuit;
 mod guess_circuit;
+mod solver;
+mod scoring;
+mod candidates;
+mod handle;
+mod codemaker;
+mod board;
+mod settings;
+mod match_play;
+mod match_view;
+mod inspector;
+mod cheat;
+mod stats;
+mod clock;
+mod seed;
+mod daily;
+mod remote;
+#[cfg(not(target_arch = "wasm32"))]
+mod server;
+#[cfg(target_arch = "wasm32")]
+mod web;
 
-use host::{EvilHost, HonestHost, Host};
+use host::{CheatingHost, EvilHost, HonestHost, Host, HostError, LazyHost};
 use eframe::egui;
-use proof::{verify, verify_guess};
-use regex::Regex;
-use utils::string_to_code;
+use crypto::CODE_LENGTH;
+use code::Code;
+use candidates::Candidates;
+use commitment::{Commitment, Opening};
+use handle::{HostHandle, Request, Response};
+use codemaker::{secret_input, Codemaker, CodemakerWindow};
+use match_view::MatchView;
+use board::{BoardConfig, BoardRow};
+use settings::{HostKind, Settings, Variant};
+use stats::Stats;
+use clock::Clock;
+use seed::GameSeed;
+use daily::Daily;
+use inspector::{Inspector, Statement};
+use cheat::{Cheat, CheatReport};
+use std::time::Duration;
 
 const GUESSES: usize = 8;
-const SEQLEN: usize = 4;
+// generous enough for a debug build proving on one core
+const HOST_TIMEOUT: Duration = Duration::from_secs(300);
+// rounds in a match unless --match says otherwise, even so both players make as many codes
+const MATCH_ROUNDS: usize = 4;
 
+type HostFactory = fn(GameSeed) -> Box<dyn Host>;
 
+fn host_factory(kind: HostKind) -> HostFactory {
+    match kind {
+        HostKind::Evil => |seed| Box::new(EvilHost::new(seed)),
+        HostKind::Cheating => |seed| Box::new(CheatingHost::new(seed)),
+        HostKind::Lazy => |seed| Box::new(LazyHost::new(seed)),
+        HostKind::Hardest => |seed| Box::new(HonestHost::hardest(seed)),
+        HostKind::Honest => |seed| Box::new(HonestHost::new(seed)),
+    }
+}
+
+
+// how the GUI starts, from the command line or, on the web, from the page's address
+struct Launch {
+    // these replace the ones in the settings
+    host: Option<HostKind>,
+    guesses: Option<usize>,
+    seed: GameSeed,
+    daily: Option<Daily>,
+    two_windows: bool,
+    match_rounds: Option<usize>,
+}
+
+impl Launch {
+    fn app(self, cc: &eframe::CreationContext) -> MyApp {
+        let Launch { host, guesses, seed, daily, two_windows, match_rounds } = self;
+        let stored = Settings::load(cc.storage);
+        let settings = Settings { host: host.unwrap_or(stored.host), guesses: guesses.unwrap_or(stored.guesses), ..stored };
+        let new_host = host_factory(settings.host);
+        let mut app = match daily {
+            Some(daily) => MyApp::daily(new_host, settings, daily),
+            None if two_windows => MyApp::two_windows(new_host, settings, seed),
+            None => MyApp::new(new_host, settings, seed),
+        };
+        app.stats = Stats::load(cc.storage);
+        app.stored = stored;
+        match (match_rounds, settings.variant) {
+            _ if two_windows || daily.is_some() => {}
+            (Some(rounds), _) => app.screen = Screen::Match(Box::new(MatchView::new(new_host, seed, rounds, settings.guesses))),
+            (None, Variant::MakeTheCode) => app.screen = Screen::Codemaker(Box::new(Codemaker::new(settings.guesses))),
+            (None, Variant::Match) => app.screen = Screen::Match(Box::new(MatchView::new(new_host, seed, MATCH_ROUNDS, settings.guesses))),
+            (None, Variant::BreakTheCode) => {}
+        }
+        app
+    }
+}
+
+// proving spreads over rayon's global pool, which is only there with the `parallel` feature
+#[cfg(not(target_arch = "wasm32"))]
+fn set_threads(threads: usize) {
+    #[cfg(feature = "parallel")]
+    if let Err(err) = rayon::ThreadPoolBuilder::new().num_threads(threads).build_global() {
+        eprintln!("could not set up {} proving threads: {}", threads, err);
+    }
+    #[cfg(not(feature = "parallel"))]
+    eprintln!("built without the `parallel` feature, proving stays on one thread (ignoring --threads {})", threads);
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    web::start();
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+const USAGE: &str = "usage: zk-mastermind [honest|hardest|evil|cheating|lazy] [--cli] [--two-windows] [--match ROUNDS]
+    [--guesses N] [--seed N] [--daily] [--date YYYY-MM-DD] [--keys FILE] [--threads N] [--prover ADDR]
+  zk-mastermind --keys FILE --daily-commitment [--date YYYY-MM-DD]
+  zk-mastermind --keys FILE --verify-daily TRANSCRIPT...
+  zk-mastermind --serve ADDR";
+
+#[cfg(not(target_arch = "wasm32"))]
 fn main() -> Result<(), eframe::Error> {
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
+    let mut host = None;
+    let mut cli = false;
+    let mut two_windows = false;
+    let mut match_rounds = None;
+    let mut guesses = None;
+    let mut seed = GameSeed::fresh();
+    let mut daily = false;
+    let mut date = None;
+    let mut print_commitment = false;
+    let mut verify_daily = None;
+    let mut keys = None;
+    let mut serve = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--cli" => cli = true,
+            "--two-windows" => two_windows = true,
+            "--match" => match args.next().and_then(|n| n.parse().ok()).filter(|n| match_play::is_fair(*n)) {
+                Some(rounds) => match_rounds = Some(rounds),
+                None => {
+                    eprintln!("--match needs an even number of rounds, so both players make as many codes");
+                    std::process::exit(2);
+                }
+            },
+            "--guesses" => match args.next().and_then(|n| n.parse().ok()).filter(|n| (1..=settings::MAX_GUESSES).contains(n)) {
+                Some(n) => guesses = Some(n),
+                None => {
+                    eprintln!("--guesses needs a number of guesses from 1 to {}", settings::MAX_GUESSES);
+                    std::process::exit(2);
+                }
+            },
+            // replays a game's codes and commitments; the proofs differ, as every run sets up its own keys
+            "--seed" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) => seed = GameSeed::reproducible(n),
+                None => {
+                    eprintln!("--seed needs a number");
+                    std::process::exit(2);
+                }
+            },
+            "--daily" => daily = true,
+            "--date" => match args.next().and_then(|d| d.parse().ok()) {
+                Some(d) => date = Some(d),
+                None => {
+                    eprintln!("--date needs a date like 2024-12-31");
+                    std::process::exit(2);
+                }
+            },
+            // the day's commitment, for publishing before anyone plays along with the keys
+            "--daily-commitment" => print_commitment = true,
+            // proving keys to load, or to write this run's to; the day's, for daily challenges
+            "--keys" => match args.next() {
+                Some(path) => keys = Some(std::path::PathBuf::from(path)),
+                None => {
+                    eprintln!("--keys needs a file to load the proving keys from or save them to");
+                    std::process::exit(2);
+                }
+            },
+            // all remaining arguments are transcripts
+            "--verify-daily" => verify_daily = Some(args.by_ref().map(std::path::PathBuf::from).collect::<Vec<_>>()),
+            "--threads" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(threads) => set_threads(threads),
+                None => {
+                    eprintln!("--threads needs a number of threads");
+                    std::process::exit(2);
+                }
+            },
+            // proves for the browser build, or for another process started with --prover
+            "--serve" => match args.next() {
+                Some(addr) => serve = Some(addr),
+                None => {
+                    eprintln!("--serve needs an address like 127.0.0.1:9090");
+                    std::process::exit(2);
+                }
+            },
+            "--prover" => match args.next() {
+                Some(addr) => remote::set_server(addr),
+                None => {
+                    eprintln!("--prover needs the address of a process started with --serve");
+                    std::process::exit(2);
+                }
+            },
+            _ => match HostKind::from_name(&arg) {
+                Some(kind) => host = Some(kind),
+                None => {
+                    eprintln!("unknown argument {:?}\n{}", arg, USAGE);
+                    std::process::exit(2);
+                }
+            },
+        }
+    }
+    if let Some(addr) = serve {
+        if let Err(err) = server::serve(&addr) {
+            eprintln!("{}: {}", addr, err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    // transcripts only show anything when checked against the keys everyone played with
+    if keys.is_none() && (print_commitment || verify_daily.is_some()) {
+        eprintln!("--daily-commitment and --verify-daily need the day's --keys\n{}", USAGE);
+        std::process::exit(2);
+    }
+    if let Some(path) = &keys {
+        if let Err(err) = proof::use_keys(path) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+    let daily_game = Daily::new(&daily::secret(), date.unwrap_or_else(daily::today));
+    if print_commitment {
+        println!("{}", daily_game.header());
+        return Ok(());
+    }
+    if let Some(paths) = verify_daily {
+        let (reports, skipped) = daily::verify_transcripts(&daily::secret(), &paths);
+        reports.iter().for_each(|report| println!("{}", report));
+        skipped.iter().for_each(|err| eprintln!("skipped {}", err));
+        return Ok(());
+    }
+    let daily = (daily || date.is_some()).then_some(daily_game);
+    if cli {
+        let new_host = host_factory(host.unwrap_or_default());
+        let played = match (daily, match_rounds) {
+            (Some(daily), _) => cli::play_daily(daily, guesses.unwrap_or(GUESSES)),
+            (None, Some(rounds)) => cli::play_match(new_host, seed, rounds, guesses.unwrap_or(GUESSES)),
+            (None, None) => cli::play(new_host, seed, guesses.unwrap_or(GUESSES)),
+        };
+        if let Err(err) = played {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    let launch = Launch { host, guesses, seed, daily, two_windows, match_rounds };
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default().with_inner_size([400.0, 475.0]),
+        viewport: egui::ViewportBuilder::default().with_inner_size([480.0, 500.0]),
         ..Default::default()
     };
     eframe::run_native(
         "Zk-mastermind",
         options,
-        Box::new(|cc| {
-            // This gives us image support:
-            egui_extras::install_image_loaders(&cc.egui_ctx);
-            Box::<MyApp<HonestHost>>::default()
-        }),
+        Box::new(move |cc| Box::new(launch.app(cc))),
     )
 }
 
-// #[derive(Default)]
-struct MyApp <H> {
-    host: H,
-    hash_commited: [u8; 32], 
-    responses: Vec<String>,
+enum Screen {
+    Board,
+    Codemaker(Box<Codemaker>),
+    // player one types the code for player two, hidden
+    HotSeat(String),
+    Match(Box<MatchView>),
+    Stats,
+}
+
+struct MyApp {
+    new_host: HostFactory,
+    // reported with the game, so it can be replayed
+    seed: GameSeed,
+    // the host runs on its own thread, so proving never blocks drawing
+    host: HostHandle,
+    commitment: Commitment,
+    config: BoardConfig,
+    rows: Vec<BoardRow>,
     guesses_cnt: usize,
-    buffer: Vec<String>,
     success: bool,
+    // the player ran out of time, which loses the game
+    timed_out: bool,
+    clock: Clock,
+    revealed: Option<Opening>,
+    error: Option<HostError>,
+    // set once the host fails a check, which ends the game in the player's favor
+    cheat: Option<CheatReport>,
+    // codes consistent with every verified answer so far, for hints
+    candidates: Candidates,
+    show_hint: bool,
+    // every proof of this game, shown in a side panel
+    inspector: Inspector,
+    show_proofs: bool,
+    settings: Settings,
+    // the settings as saved, without the host and the guesses the command line gave for this run
+    stored: Settings,
+    stats: Stats,
+    screen: Screen,
+    // the board's host is a human sharing the screen
+    hot_seat: bool,
+    // the codemaker's window, when the host is a human there
+    window: Option<CodemakerWindow>,
+    // the day's challenge being played, and where its transcript went
+    daily: Option<Daily>,
+    daily_saved: Option<Result<std::path::PathBuf, String>>,
 }
 
-// TODO: do we need all of this?
-impl <H> Default for MyApp<H> where H: Host {
-    fn default() -> Self {
-        let host = H::new();
-        let (hash, proof) = host.get_hash_with_proof();
-        assert!(verify(hash, proof));
+impl MyApp {
+    fn new(new_host: HostFactory, settings: Settings, seed: GameSeed) -> Self {
+        Self::with_handle(new_host, settings, seed, HostHandle::spawn(new_host(seed)).with_timeout(HOST_TIMEOUT))
+    }
+
+    // the codemaker's window serves the board's requests, without a timeout as it waits for a person
+    fn two_windows(new_host: HostFactory, settings: Settings, seed: GameSeed) -> Self {
+        let (handle, endpoint) = handle::channel();
+        Self { window: Some(CodemakerWindow::new(endpoint, seed)), ..Self::with_handle(new_host, settings, seed, handle) }
+    }
+
+    // the day's code, always from an honest host. The day's seed gives the code away, so the board
+    // gets a seed of its own, for the games after this one
+    fn daily(new_host: HostFactory, settings: Settings, daily: Daily) -> Self {
+        let host = HostHandle::spawn(Box::new(HonestHost::new(daily.seed))).with_timeout(HOST_TIMEOUT);
+        Self { daily: Some(daily), ..Self::with_handle(new_host, settings, GameSeed::fresh(), host) }
+    }
+
+    fn with_handle(new_host: HostFactory, settings: Settings, seed: GameSeed, mut host: HostHandle) -> Self {
+        let error = host.send(Request::Commit).err();
+        let config = BoardConfig::new(&settings);
         Self {
+            new_host,
+            seed,
             host,
-            hash_commited: hash,
-            responses: vec![String::new(); GUESSES],
+            commitment: Commitment { hash: [0; 32] },
+            rows: (0..config.rows).map(|_| BoardRow { pegs: config.empty_row(), feedback: None, rejected: false }).collect(),
+            config,
             guesses_cnt: 0,
-            buffer: vec![String::new(); GUESSES],
             success: false,
+            timed_out: false,
+            clock: Clock::new(settings.time_limit),
+            revealed: None,
+            error,
+            cheat: None,
+            candidates: Candidates::all(),
+            show_hint: false,
+            inspector: Inspector::default(),
+            show_proofs: false,
+            settings,
+            stored: settings,
+            stats: Stats::default(),
+            screen: Screen::Board,
+            hot_seat: false,
+            window: None,
+            daily: None,
+            daily_saved: None,
+        }
+    }
+
+    fn is_over(&self) -> bool {
+        self.success || self.timed_out || self.cheat.is_some() || self.guesses_cnt == self.config.rows
+    }
+
+    fn submit(&mut self, i: usize) {
+        if self.host.pending().is_some() {
+            return;
+        }
+        let colors: Option<Vec<u8>> = self.rows[i].pegs.iter().copied().collect();
+        if let Some(Ok(colors)) = colors.map(<[u8; CODE_LENGTH]>::try_from) {
+            if let Err(err) = self.host.send(Request::Guess(Code { colors })) {
+                self.error = Some(err);
+            }
+        } // otherwise, do nothing
+    }
+
+    // replaces the game, the statistics carry over
+    fn start_over(&mut self, next: MyApp) {
+        *self = MyApp { stats: std::mem::take(&mut self.stats), stored: self.stored, ..next };
+    }
+
+    fn against_computer(&self) -> bool {
+        !self.hot_seat && self.window.is_none()
+    }
+
+    // another game against the same kind of opponent
+    fn restart(&mut self) {
+        if self.window.is_some() {
+            self.start_over(MyApp::two_windows(self.new_host, self.settings, self.seed.next()));
+        } else if self.hot_seat {
+            self.screen = Screen::HotSeat(String::new());
+        } else {
+            self.start_over(MyApp::new(self.new_host, self.settings, self.seed.next()));
+        }
+    }
+
+    // the clock runs while the player may guess; running out ends the game like the last wrong guess
+    fn tick(&mut self, ctx: &egui::Context, players_turn: bool) {
+        if !players_turn {
+            self.clock.stop();
+            return;
+        }
+        self.clock.start();
+        if self.clock.is_out() {
+            self.clock.stop();
+            self.timed_out = true;
+            self.error = self.host.send(Request::Surrender).err();
+        } else if self.clock.remaining().is_some() {
+            ctx.request_repaint_after(Duration::from_millis(250));
         }
     }
-}
 
-impl <H>  MyApp <H> where H:Host {
-    fn submit(&mut self, i: usize) -> () {
-        // TODO: make this global
-        let pattern: Regex = Regex::new(r"^[a-h]{4}$").unwrap();
-        let mut s = self.buffer[i].clone();
-        if pattern.is_match(&mut s) {
-            let (same, common, proof) = self.host.guess(s.clone());
-            assert!(verify_guess(string_to_code(s.clone()).colors, self.hash_commited, same as u8, common as u8, proof));
-            let mut response = ['x'; SEQLEN];
-            for j in 0usize..common {
-                response[j] = 'y';
+    // picks up the host's response once it is there
+    fn poll(&mut self) {
+        let Some((request, response)) = self.host.poll() else {
+            return;
+        };
+        match (request, response) {
+            (_, Err(err)) => self.error = Some(err),
+            (Request::Commit, Ok(Response::Committed(commitment, proof))) => {
+                self.commitment = commitment;
+                self.stats.record_proof(proof.proving_time);
+                if !self.inspector.record(Statement::Commitment(commitment), proof) {
+                    self.cheat = Some(CheatReport::new(Cheat::Commitment(commitment)));
+                    self.error = self.host.send(Request::Surrender).err();
+                }
             }
-            for j in 0usize..same {
-                response[j] = 'z';
+            (Request::Guess(guess), Ok(Response::Answered(answer, proof))) => {
+                let row = self.guesses_cnt;
+                self.rows[row].feedback = Some(answer);
+                self.guesses_cnt += 1;
+                self.stats.record_proof(proof.proving_time);
+                if self.inspector.record(Statement::Guess { guess, commitment: self.commitment, answer }, proof) {
+                    self.candidates.retain(&guess, answer);
+                    self.success = answer.is_win();
+                } else {
+                    self.rows[row].rejected = true;
+                    self.cheat = Some(CheatReport::new(Cheat::Answer { row, guess, answer }));
+                }
+                if self.is_over() {
+                    self.error = self.host.send(Request::Surrender).err();
+                }
             }
-            self.guesses_cnt += 1;
-            let response_ = response.iter().collect::<String>();
-            if response_.eq("zzzz") {
-               self.success = true;
+            (Request::Surrender, Ok(Response::Surrendered(opening))) => {
+                self.revealed = opening;
+                let cheat = match opening {
+                    Some(opening) if !opening.opens(&self.commitment) => Some(Cheat::Opening(opening)),
+                    None if !self.success => Some(Cheat::Withheld),
+                    _ => None,
+                };
+                if self.cheat.is_none() {
+                    self.cheat = cheat.map(CheatReport::new);
+                }
+                if self.against_computer() {
+                    self.stats.record_game(self.success.then_some(self.guesses_cnt), self.cheat.is_some());
+                }
             }
-            self.responses[i] = response_;
-        } // otherwise, do nothing
+            _ => self.error = Some(HostError::ProtocolViolation("response does not fit the request")),
+        }
     }
 }
 
 
-impl <H> eframe::App for MyApp <H> where H:Host {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            macro_rules! new_row {
-                ($i:literal) => {
-                    ui.horizontal(|ui| {
-                        ui.add_enabled_ui(((self.guesses_cnt) == $i), |ui| {
-                            ui.label(format!("Guess {}: ", ($i)+1));
-                            let (response, painter) = ui.allocate_painter(
-                                egui::Vec2::new(120.0, 30.0),
-                                egui::Sense::hover(),
-                            );
-                            for j in 0..self.buffer[$i].len() {
-                                let cx = 15.0 + 30.0*(j as f32);
-                                painter.circle_filled(
-                                    response.rect.min + egui::Vec2::new(cx, 15.0),
-                                    10.0,
-                                    consts::COLORS[&self.buffer[$i].chars().nth(j).unwrap()]
-                                );
-                            }
+impl eframe::App for MyApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        // the rest, like the mode last played, is the player's own whatever the command line said
+        Settings { host: self.stored.host, guesses: self.stored.guesses, ..self.settings }.save(storage);
+        self.stats.save(storage);
+    }
 
-                            if ui.button("Confirm").clicked() {
-                                self.submit($i);
-                            }
-                        });
-                        let (response2, painter2) = ui.allocate_painter(
-                            egui::Vec2::new(120.0, 30.0),
-                            egui::Sense::hover(),
-                        );
-                        for j in 0..self.responses[$i].len() {
-                            let cx = 15.0 + 30.0*(j as f32);
-                            painter2.circle_filled(
-                                response2.rect.min + egui::Vec2::new(cx, 15.0),
-                                10.0,
-                                consts::COLORS[&self.responses[$i].chars().nth(j).unwrap()]
-                            );
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.poll();
+        if let Some(window) = &mut self.window {
+            ctx.show_viewport_immediate(
+                egui::ViewportId::from_hash_of("codemaker"),
+                egui::ViewportBuilder::default().with_title("Zk-mastermind codemaker").with_inner_size([480.0, 400.0]),
+                |ctx, class| {
+                    // the web has no second window, there the codemaker's floats over the board
+                    if class == egui::ViewportClass::Embedded {
+                        egui::Window::new("Codemaker").show(ctx, |ui| window.ui(ui, &self.config));
+                    } else {
+                        egui::CentralPanel::default().show(ctx, |ui| window.ui(ui, &self.config));
+                    }
+                },
+            );
+        }
+        egui::TopBottomPanel::top("mode").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let against_computer = self.against_computer();
+                let daily = self.daily.is_some();
+                if ui.selectable_label(matches!(self.screen, Screen::Board) && against_computer && !daily, "Break the code").clicked() {
+                    self.settings.variant = Variant::BreakTheCode;
+                    if against_computer && !daily {
+                        self.screen = Screen::Board;
+                    } else {
+                        self.start_over(MyApp::new(self.new_host, self.settings, self.seed.next()));
+                    }
+                }
+                if ui.selectable_label(matches!(self.screen, Screen::Codemaker(_)), "Make the code").clicked()
+                    && !matches!(self.screen, Screen::Codemaker(_))
+                {
+                    self.settings.variant = Variant::MakeTheCode;
+                    self.screen = Screen::Codemaker(Box::new(Codemaker::new(self.settings.guesses)));
+                }
+                if ui.selectable_label(matches!(self.screen, Screen::Match(_)), "Match").clicked()
+                    && !matches!(self.screen, Screen::Match(_))
+                {
+                    self.settings.variant = Variant::Match;
+                    self.screen = Screen::Match(Box::new(MatchView::new(self.new_host, self.seed.next(), MATCH_ROUNDS, self.settings.guesses)));
+                }
+                if ui.selectable_label(matches!(self.screen, Screen::Board) && daily, "Daily").clicked() {
+                    if daily {
+                        self.screen = Screen::Board;
+                    } else {
+                        let today = Daily::new(&daily::secret(), daily::today());
+                        self.start_over(MyApp::daily(self.new_host, self.settings, today));
+                    }
+                }
+                if ui.selectable_label(self.hot_seat, "Two players").clicked() && !self.hot_seat {
+                    self.window = None;
+                    self.hot_seat = true;
+                    self.screen = Screen::HotSeat(String::new());
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.menu_button("⚙ Settings", |ui| {
+                        // the rows keep their pegs and their number, only the drawing changes
+                        // once changed here the overrides are the player's choice too
+                        if self.settings.ui(ui) {
+                            self.stored = self.settings;
+                            self.config = BoardConfig { rows: self.config.rows, ..BoardConfig::new(&self.settings) };
+                            self.new_host = host_factory(self.settings.host);
                         }
                     });
+                    if ui.selectable_label(matches!(self.screen, Screen::Stats), "Statistics").clicked() {
+                        self.screen = Screen::Stats;
+                    }
+                });
+            });
+        });
+        // the clock only runs while the board is in sight
+        if !matches!(self.screen, Screen::Board) {
+            self.clock.pause();
+        }
+        match &mut self.screen {
+            Screen::Board => {}
+            Screen::Codemaker(codemaker) => {
+                egui::CentralPanel::default().show(ctx, |ui| codemaker.ui(ui, &self.config));
+                return;
+            }
+            Screen::Match(view) => {
+                egui::CentralPanel::default().show(ctx, |ui| view.ui(ui, &self.config));
+                return;
+            }
+            Screen::Stats => {
+                egui::CentralPanel::default().show(ctx, |ui| self.stats.ui(ui));
+                return;
+            }
+            Screen::HotSeat(secret) => {
+                let committed = egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.label("Player one, pick the code for player two to break:");
+                    secret_input(ui, &self.config, secret, true)
+                }).inner;
+                if let Some(code) = committed {
+                    let seed = self.seed.next();
+                    let host = HostHandle::spawn(Box::new(HonestHost::with_code(code, seed))).with_timeout(HOST_TIMEOUT);
+                    self.start_over(MyApp { hot_seat: true, ..MyApp::with_handle(self.new_host, self.settings, seed, host) });
                 }
+                return;
             }
-            // TODO: can we get rid of this?
-            new_row!(0usize);
-            new_row!(1usize);
-            new_row!(2usize);
-            new_row!(3usize);
-            new_row!(4usize);
-            new_row!(5usize);
-            new_row!(6usize);
-            new_row!(7usize);
-
-            ui.add_space(15.0);
-            ui.vertical_centered( |ui| {
-                ui.add(egui::Image::new(egui::include_image!("../data/color_map.png")).max_width(200.0));
-            });
-            ui.add_space(15.0);
-
-            for (letter, _color) in consts::COLORS.clone().into_iter() {
-                let key = egui::Key::from_name(&letter.to_string()).unwrap();
-                if ui.input(|u| u.key_pressed(key)) {
-                    if self.buffer[self.guesses_cnt].len() < SEQLEN {
-                        self.buffer[self.guesses_cnt].push(letter);
+        }
+        if self.show_proofs {
+            egui::SidePanel::right("proofs").show(ctx, |ui| self.inspector.ui(ui, &self.config));
+        }
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if let Some(daily) = self.daily {
+                ui.horizontal(|ui| {
+                    ui.heading(format!("Daily challenge of {}", daily.date));
+                    // everyone's host must commit to the code announced for the day
+                    if self.commitment == daily.commitment() {
+                        ui.colored_label(egui::Color32::GREEN, "✔").on_hover_text(format!("the announced commitment {}", daily.commitment()));
                     }
-                }
+                });
+            }
+            let active = (self.host.pending().is_none() && !self.is_over()).then_some(self.guesses_cnt);
+            self.tick(ctx, active.is_some());
+            if let Some(i) = board::board_ui(ui, &self.config, egui::Id::new(self.commitment), &mut self.rows, active) {
+                self.submit(i);
             }
-            if ui.input(|u| u.key_pressed(egui::Key::Backspace)) {
-                self.buffer[self.guesses_cnt].pop();
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                // no cancelling, the host would still count the guess and could not take it again
+                if self.host.pending().is_some() {
+                    ui.spinner();
+                    ui.label("proving…");
+                }
+                if let Some(remaining) = self.clock.remaining().filter(|_| !self.is_over()) {
+                    let text = format!("⏱ {}:{:02}", remaining.as_secs() / 60, remaining.as_secs() % 60);
+                    if remaining.as_secs() < 10 {
+                        ui.colored_label(egui::Color32::RED, text);
+                    } else {
+                        ui.label(text);
+                    }
+                }
+                ui.toggle_value(&mut self.show_proofs, "Proofs");
+                ui.toggle_value(&mut self.show_hint, "Hint");
+                if self.show_hint {
+                    match self.candidates.first() {
+                        Some(code) => ui.label(format!("{} codes still fit the answers, e.g. {}", self.candidates.len(), code)),
+                        None => ui.label("no code fits all the answers"),
+                    };
+                }
+            });
+            if let Some(err) = &self.error {
+                ui.colored_label(egui::Color32::RED, err.to_string());
             }
-            if ui.input(|u| u.key_pressed(egui::Key::Enter)) {
-                self.submit(self.guesses_cnt);
+            // the seed gives the code away; a person's code and the day's do not follow it at all
+            let replayable = self.daily.is_none() && !self.hot_seat && self.window.is_none();
+            if self.is_over() && replayable {
+                ui.weak(format!("game seed {}", self.seed))
+                    .on_hover_text(format!("--seed {} replays this code and commitment, the proofs will differ", self.seed));
             }
             ui.vertical_centered(|ui| {
-                if self.guesses_cnt < 8 && !self.success {
+                if !self.is_over() {
                     ui.set_opacity(0.0);
                 }
                 ui.style_mut().override_text_style = Some(egui::TextStyle::Heading);
-                ui.label(format!("{}", if self.success { "You won!" } else {"You lost!"}));
-                if ui.button("New game!").clicked() {
-                    *self = MyApp::<H>::default();
-                }
-                let (response, painter) = ui.allocate_painter(
-                    egui::Vec2::new(120.0, 30.0),
-                    egui::Sense::hover(),
-                );
-                for j in 0..4 {
-                    let cx = 15.0 + 30.0*(j as f32);
-                    painter.circle_filled(
-                        response.rect.min + egui::Vec2::new(cx, 15.0),
-                        10.0,
-                        consts::COLORS[&self.host.surrender().chars().nth(j).unwrap()].gamma_multiply(
-                            (self.guesses_cnt == 8 || self.success) as i32 as f32  // B)
-                        )
-                    );
+                ui.label(if self.cheat.is_some() {
+                    "You won by forfeit!"
+                } else if self.success {
+                    "You won!"
+                } else if self.timed_out {
+                    "Out of time, you lost!"
+                } else {
+                    "You lost!"
+                });
+                // hidden is not enough, an invisible button still takes clicks
+                if self.is_over() {
+                    ui.horizontal(|ui| {
+                        if ui.button("New game!").clicked() {
+                            self.restart();
+                        }
+                        if let Some(report) = &mut self.cheat {
+                            if ui.button("Why?").clicked() {
+                                report.reopen();
+                            }
+                        }
+                        if let Some(daily) = self.daily {
+                            if ui.button("Save transcript").on_hover_text("played with the day's --keys, to compare with other players with --verify-daily").clicked() {
+                                let result = if self.success { "broken" } else { "not broken" };
+                                let body = format!("{}result: {} in {} guesses\n", self.inspector.export(), result, self.guesses_cnt);
+                                self.daily_saved = Some(daily.save_transcript(&body).map_err(|err| err.to_string()));
+                            }
+                        }
+                    });
+                }
+                match &self.daily_saved {
+                    Some(Ok(path)) => ui.label(egui::RichText::new(format!("saved to {}", path.display())).small()),
+                    Some(Err(err)) => ui.colored_label(egui::Color32::RED, egui::RichText::new(err).small()),
+                    None => ui.label(""),
+                };
+                let revealed = match self.revealed {
+                    Some(opening) => opening.code.colors.map(Some).to_vec(),
+                    None => self.config.empty_row(),
+                };
+                board::pegs_ui(ui, &self.config, &revealed);
+                if self.revealed.is_some_and(|opening| !opening.opens(&self.commitment)) {
+                    ui.colored_label(egui::Color32::RED, "This code does not match the commitment!");
                 }
             });
         });
+        if let Some(report) = &mut self.cheat {
+            let transcript = || {
+                let code = match self.revealed {
+                    Some(opening) => format!("code: {} with salt {}", opening.code, inspector::hex(&opening.salt)),
+                    None => "code: not revealed".to_string(),
+                };
+                format!("seed: {}\n{}{}\n", self.seed, self.inspector.export(), code)
+            };
+            report.ui(ctx, &self.commitment, transcript);
+        }
     }
 }
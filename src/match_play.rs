@@ -0,0 +1,280 @@
+use std::fmt;
+use crate::code::Code;
+use crate::commitment::{Commitment, Opening};
+use crate::feedback::Feedback;
+use crate::proof::{verify, verify_guess, Proof};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Player {
+    One,
+    Two,
+}
+
+impl Player {
+    pub fn other(self) -> Player {
+        match self {
+            Player::One => Player::Two,
+            Player::Two => Player::One,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Exchange {
+    pub guess: Code,
+    pub answer: Feedback,
+    pub verified: bool,
+}
+
+// everything both players can check about a round afterwards
+#[derive(Clone, Debug)]
+pub struct Transcript {
+    pub codemaker: Player,
+    pub commitment: Commitment,
+    pub commitment_verified: bool,
+    pub exchanges: Vec<Exchange>,
+    pub opening: Option<Opening>,
+    pub finished: bool,
+    // the guess budget of the round
+    pub guesses: usize,
+}
+
+impl Transcript {
+    pub fn is_broken(&self) -> bool {
+        self.exchanges.last().is_some_and(|exchange| exchange.answer.is_win())
+    }
+
+    // a failed proof or an opening for another commitment; a host may refuse to reveal its code,
+    // that only matters if the code was never broken
+    pub fn is_cheating(&self) -> bool {
+        !self.commitment_verified
+            || self.exchanges.iter().any(|exchange| !exchange.verified)
+            || self.opening.is_some_and(|opening| !opening.opens(&self.commitment))
+            || (self.finished && !self.is_broken() && self.opening.is_none())
+    }
+
+    fn is_over(&self) -> bool {
+        self.is_cheating() || self.is_broken() || self.exchanges.len() == self.guesses
+    }
+
+    // one point per guess, and one more if the code was not broken at all;
+    // a codemaker caught cheating gets nothing
+    pub fn points(&self) -> usize {
+        if self.is_cheating() {
+            0
+        } else if self.is_broken() {
+            self.exchanges.len()
+        } else {
+            self.guesses + 1
+        }
+    }
+}
+
+impl fmt::Display for Transcript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "codemaker: player {:?}", self.codemaker)?;
+        writeln!(f, "commitment: {} ({})", self.commitment, if self.commitment_verified { "verified" } else { "NOT verified" })?;
+        for (i, exchange) in self.exchanges.iter().enumerate() {
+            writeln!(f, "guess {}: {} {} ({})", i + 1, exchange.guess, exchange.answer,
+                if exchange.verified { "verified" } else { "NOT verified" })?;
+        }
+        match self.opening {
+            Some(opening) if opening.opens(&self.commitment) => writeln!(f, "code: {} (opens the commitment)", opening.code)?,
+            Some(opening) => writeln!(f, "code: {} (does NOT open the commitment)", opening.code)?,
+            None => writeln!(f, "code: not revealed")?,
+        }
+        write!(f, "points for the codemaker: {}", self.points())
+    }
+}
+
+// both players make as many codes only in an even number of rounds
+pub fn is_fair(rounds: usize) -> bool {
+    rounds > 0 && rounds % 2 == 0
+}
+
+// a match of several rounds with the players taking turns as codemaker, player one first;
+// every proof is checked as it is recorded
+pub struct Match {
+    rounds: usize,
+    guesses: usize,
+    transcripts: Vec<Transcript>,
+}
+
+impl Match {
+    pub fn new(rounds: usize, guesses: usize) -> Self {
+        Match { rounds, guesses, transcripts: Vec::new() }
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    pub fn guesses(&self) -> usize {
+        self.guesses
+    }
+
+    pub fn transcripts(&self) -> &[Transcript] {
+        &self.transcripts
+    }
+
+    fn current(&mut self) -> Option<&mut Transcript> {
+        self.transcripts.last_mut().filter(|transcript| !transcript.finished)
+    }
+
+    // the codemaker of the round being played, or of the next one
+    pub fn codemaker(&self) -> Player {
+        match self.transcripts.last() {
+            Some(transcript) if !transcript.finished => transcript.codemaker,
+            Some(transcript) => transcript.codemaker.other(),
+            None => Player::One,
+        }
+    }
+
+    pub fn in_round(&self) -> bool {
+        self.transcripts.last().is_some_and(|transcript| !transcript.finished)
+    }
+
+    // whether the round still waits for guesses; false once the code is broken,
+    // the guesses are used up or a proof failed
+    pub fn wants_guess(&self) -> bool {
+        self.transcripts.last().is_some_and(|transcript| !transcript.finished && !transcript.is_over())
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.transcripts.len() == self.rounds && !self.in_round()
+    }
+
+    // returns whether the commitment proof verified
+    pub fn start_round(&mut self, commitment: Commitment, proof: &Proof) -> bool {
+        let verified = verify(&commitment, proof);
+        self.start_verified(commitment, verified);
+        verified
+    }
+
+    // the rest of start_round, apart so the tests need no proofs
+    fn start_verified(&mut self, commitment: Commitment, verified: bool) {
+        assert!(!self.in_round() && !self.is_over(), "no round to start");
+        self.transcripts.push(Transcript {
+            codemaker: self.codemaker(),
+            commitment,
+            commitment_verified: verified,
+            exchanges: Vec::new(),
+            opening: None,
+            finished: false,
+            guesses: self.guesses,
+        });
+    }
+
+    // returns whether the answer's proof verified
+    pub fn record(&mut self, guess: Code, answer: Feedback, proof: &Proof) -> bool {
+        let commitment = self.current().expect("no round in progress").commitment;
+        let verified = verify_guess(guess, &commitment, answer, proof);
+        self.record_verified(guess, answer, verified);
+        verified
+    }
+
+    fn record_verified(&mut self, guess: Code, answer: Feedback, verified: bool) {
+        let transcript = self.current().expect("no round in progress");
+        transcript.exchanges.push(Exchange { guess, answer, verified });
+    }
+
+    pub fn finish_round(&mut self, opening: Option<Opening>) {
+        let transcript = self.current().expect("no round in progress");
+        transcript.opening = opening;
+        transcript.finished = true;
+    }
+
+    pub fn scores(&self) -> [usize; 2] {
+        let mut scores = [0; 2];
+        for transcript in self.transcripts.iter().filter(|transcript| transcript.finished) {
+            scores[transcript.codemaker.index()] += transcript.points();
+        }
+        scores
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+    use crate::scoring;
+    use super::*;
+
+    fn opening(code: &str) -> Opening {
+        Opening { code: code.parse().unwrap(), salt: [0; 32] }
+    }
+
+    // plays a round through with every proof verifying unless said otherwise
+    fn round(game: &mut Match, opening: Opening, guesses: &[&str], verified: bool, revealed: bool) {
+        game.start_verified(opening.commit(), true);
+        for guess in guesses {
+            let guess: Code = guess.parse().unwrap();
+            game.record_verified(guess, scoring::feedback(&opening.code, &guess), verified);
+        }
+        game.finish_round(revealed.then_some(opening));
+    }
+
+    #[test]
+    fn only_positive_even_rounds_are_fair() {
+        assert!(is_fair(2) && is_fair(4));
+        assert!(!is_fair(0) && !is_fair(1) && !is_fair(3));
+    }
+
+    #[test]
+    fn codemakers_take_turns() {
+        let mut game = Match::new(4, 3);
+        for round in 0..4 {
+            let codemaker = if round % 2 == 0 { Player::One } else { Player::Two };
+            assert_eq!(game.codemaker(), codemaker);
+            game.start_verified(opening("abcd").commit(), true);
+            assert_eq!(game.codemaker(), codemaker);
+            game.finish_round(Some(opening("abcd")));
+        }
+        assert!(game.is_over());
+    }
+
+    #[test]
+    fn codemakers_score_the_guesses_they_took() {
+        let mut game = Match::new(2, 3);
+        round(&mut game, opening("abcd"), &["aabb", "abcd"], true, true);
+        assert_eq!(game.transcripts()[0].points(), 2);
+        // not broken in the budget, one point more
+        round(&mut game, opening("abcd"), &["aabb", "bbcc", "ccdd"], true, true);
+        assert!(!game.wants_guess());
+        assert_eq!(game.transcripts()[1].points(), 4);
+        assert_eq!(game.scores(), [2, 4]);
+    }
+
+    #[test]
+    fn cheating_codemakers_score_nothing() {
+        let cheats: [fn(&mut Match); 3] = [
+            |game| round(game, opening("abcd"), &["aabb"], false, true),
+            // an unbroken code which is never revealed
+            |game| round(game, opening("abcd"), &["aabb", "bbcc", "ccdd"], true, false),
+            |game| {
+                game.start_verified(opening("abcd").commit(), true);
+                game.finish_round(Some(opening("dcba")));
+            },
+        ];
+        for cheat in cheats {
+            let mut game = Match::new(2, 3);
+            cheat(&mut game);
+            assert!(game.transcripts()[0].is_cheating());
+            assert_eq!(game.scores(), [0, 0]);
+        }
+        let mut game = Match::new(2, 3);
+        game.start_verified(opening("abcd").commit(), false);
+        assert!(!game.wants_guess());
+        assert!(game.transcripts()[0].is_cheating());
+
+        // a broken code need not be revealed
+        let mut game = Match::new(2, 3);
+        round(&mut game, opening("abcd"), &["abcd"], true, false);
+        assert!(!game.transcripts()[0].is_cheating());
+        assert_eq!(game.scores(), [1, 0]);
+    }
+}
@@ -0,0 +1,206 @@
+use eframe::egui;
+use crate::code::Code;
+use crate::codemaker::secret_input;
+use crate::board::{self, BoardConfig};
+use crate::handle::{HostHandle, Request, Response};
+use crate::host::{HonestHost, HostError};
+use crate::match_play::{Match, Player, Transcript};
+use crate::seed::GameSeed;
+use crate::solver::Solver;
+use crate::{HostFactory, HOST_TIMEOUT};
+
+// a match against the computer, the human is player one and makes the first code
+pub struct MatchView {
+    new_host: HostFactory,
+    // round i is played under the seed derived with i
+    seed: GameSeed,
+    game: Match,
+    host: Option<HostHandle>,
+    solver: Solver,
+    input: String,
+    error: Option<HostError>,
+}
+
+fn player_name(player: Player) -> &'static str {
+    match player {
+        Player::One => "You",
+        Player::Two => "Computer",
+    }
+}
+
+fn transcript_ui(ui: &mut egui::Ui, config: &BoardConfig, transcript: &Transcript) {
+    ui.label(format!("{} made the code, committed to {}", player_name(transcript.codemaker), transcript.commitment));
+    if !transcript.commitment_verified {
+        ui.colored_label(egui::Color32::RED, "✘ commitment proof failed");
+    }
+    for (i, exchange) in transcript.exchanges.iter().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label(format!("Guess {}: ", i + 1));
+            board::code_ui(ui, config, &exchange.guess);
+            board::feedback_ui(ui, config, ui.id().with(i), Some(exchange.answer));
+            if exchange.verified {
+                ui.colored_label(egui::Color32::GREEN, "✔");
+            } else {
+                ui.colored_label(egui::Color32::RED, "✘ proof failed");
+            }
+        });
+    }
+    if transcript.finished {
+        match transcript.opening {
+            Some(opening) => {
+                ui.horizontal(|ui| {
+                    ui.label("Code: ");
+                    board::code_ui(ui, config, &opening.code);
+                    if !opening.opens(&transcript.commitment) {
+                        ui.colored_label(egui::Color32::RED, "does not match the commitment!");
+                    }
+                });
+            }
+            None => {
+                ui.label("The code was not revealed");
+            }
+        }
+        ui.label(format!("{} points for {}", transcript.points(), player_name(transcript.codemaker)));
+    }
+}
+
+impl MatchView {
+    pub fn new(new_host: HostFactory, seed: GameSeed, rounds: usize, guesses: usize) -> Self {
+        MatchView {
+            new_host,
+            seed,
+            game: Match::new(rounds, guesses),
+            host: None,
+            solver: Solver::new(),
+            input: String::new(),
+            error: None,
+        }
+    }
+
+    // for the round about to start, counting from one
+    fn round_seed(&self) -> GameSeed {
+        self.seed.derive(self.game.transcripts().len() as u64 + 1)
+    }
+
+    fn start_round(&mut self, host: HostHandle) {
+        let mut host = host.with_timeout(HOST_TIMEOUT);
+        self.error = host.send(Request::Commit).err();
+        self.host = Some(host);
+        self.solver = Solver::new();
+    }
+
+    fn computer_guesses(&self) -> bool {
+        self.game.codemaker() == Player::One
+    }
+
+    fn send(&mut self, request: Request) {
+        if let Some(host) = &mut self.host {
+            self.error = host.send(request).err();
+        }
+    }
+
+    // the computer guesses as soon as it may, and the host reveals its code once the round is over
+    fn next_request(&mut self) {
+        if !self.game.wants_guess() {
+            self.send(Request::Surrender);
+        } else if self.computer_guesses() {
+            match self.solver.next_guess() {
+                Some(guess) => self.send(Request::Guess(guess)),
+                None => self.error = Some(HostError::ProtocolViolation("no code is consistent with the answers")),
+            }
+        }
+    }
+
+    fn poll(&mut self) {
+        let Some((request, response)) = self.host.as_mut().and_then(HostHandle::poll) else {
+            return;
+        };
+        match (request, response) {
+            (_, Err(err)) => self.error = Some(err),
+            (Request::Commit, Ok(Response::Committed(commitment, proof))) => {
+                self.game.start_round(commitment, &proof);
+                self.next_request();
+            }
+            (Request::Guess(guess), Ok(Response::Answered(answer, proof))) => {
+                self.game.record(guess, answer, &proof);
+                self.solver.learn(&guess, answer);
+                self.next_request();
+            }
+            (Request::Surrender, Ok(Response::Surrendered(opening))) => {
+                self.game.finish_round(opening);
+                self.host = None;
+            }
+            _ => self.error = Some(HostError::ProtocolViolation("response does not fit the request")),
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, config: &BoardConfig) {
+        self.poll();
+        let [you, computer] = self.game.scores();
+        let round = (self.game.transcripts().len() + !self.game.in_round() as usize).min(self.game.rounds());
+        ui.heading(format!("Round {} of {} — you {} : {} computer", round, self.game.rounds(), you, computer));
+        // it gives away the computer's codes of the rounds to come
+        if self.game.is_over() {
+            ui.weak(format!("match seed {}", self.seed));
+        }
+        ui.add_space(10.0);
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (i, transcript) in self.game.transcripts().iter().enumerate() {
+                if transcript.finished {
+                    egui::CollapsingHeader::new(format!("Round {}", i + 1)).show(ui, |ui| transcript_ui(ui, config, transcript));
+                }
+            }
+            if let Some(transcript) = self.game.transcripts().last().filter(|transcript| !transcript.finished) {
+                transcript_ui(ui, config, transcript);
+            }
+
+            let pending = self.host.as_ref().is_some_and(|host| host.pending().is_some());
+            if pending {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("proving…");
+                });
+            }
+            if let Some(err) = &self.error {
+                ui.colored_label(egui::Color32::RED, err.to_string());
+            }
+
+            if self.game.is_over() {
+                ui.add_space(10.0);
+                ui.heading(match you.cmp(&computer) {
+                    std::cmp::Ordering::Greater => "You won the match!",
+                    std::cmp::Ordering::Less => "The computer won the match!",
+                    std::cmp::Ordering::Equal => "The match is a draw.",
+                });
+                if ui.button("New match").clicked() {
+                    *self = MatchView::new(self.new_host, self.seed.next(), self.game.rounds(), self.game.guesses());
+                }
+            } else if self.host.is_none() {
+                if self.computer_guesses() {
+                    ui.label("Your turn to make the code:");
+                    if let Some(code) = secret_input(ui, config, &mut self.input, false) {
+                        self.input.clear();
+                        let seed = self.round_seed();
+                        self.start_round(HostHandle::spawn(Box::new(HonestHost::with_code(code, seed))));
+                    }
+                } else if ui.button("Start the next round").clicked() {
+                    self.start_round(HostHandle::spawn((self.new_host)(self.round_seed())));
+                }
+            } else if !self.computer_guesses() && !pending && self.game.wants_guess() {
+                ui.horizontal(|ui| {
+                    let edit = ui.text_edit_singleline(&mut self.input);
+                    let guess = self.input.parse::<Code>().ok();
+                    if let Some(guess) = guess {
+                        board::code_ui(ui, config, &guess);
+                    }
+                    let entered = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if let Some(guess) = guess.filter(|_| ui.button("Guess").clicked() || entered) {
+                        self.input.clear();
+                        self.send(Request::Guess(guess));
+                    }
+                });
+            }
+        });
+    }
+}
@@ -1,104 +1,289 @@
 use ark_bls12_381::Fr;
 use ark_groth16::Groth16;
-use ark_std::rand::SeedableRng;
-use ark_std::{iterable::Iterable, rand::rngs::StdRng};
+use ark_std::rand::{CryptoRng, RngCore};
 use ark_groth16::r1cs_to_qap::LibsnarkReduction;
 use ark_snark::SNARK;
-use ark_ff::{Fp, MontBackend};
-use ark_bls12_381::FrConfig;
-use ark_std::Zero;
-use ark_std::One;
 
-use ark_bls12_381::Config;
-use ark_ec::bls12::Bls12;
-use ark_groth16::VerifyingKey;
+use ark_groth16::{ProvingKey, VerifyingKey};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::Duration;
+use web_time::Instant;
 
+use crate::feedback::Feedback;
 use crate::guess_circuit::GuessCircuit;
+use crate::inspector::hex;
+use crate::remote::{self, ProvingRequest};
 
-use super::crypto::Code;
+use super::code::Code;
+use super::commitment::{Commitment, Opening};
 use super::code_circuit::CodeDeclarationCircuit;
-use super::crypto::CODE_LENGTH;
+use super::crypto::{pack_hash, CODE_LENGTH};
 
 type Curve = ark_bls12_381::Bls12_381;
 type CircuitField = Fr;
 
-struct PublicInput(Vec<Fp<MontBackend<FrConfig, 4>, 4>>);
+pub type Key = ProvingKey<Curve>;
+pub type Verifier = VerifyingKey<Curve>;
+
+struct PublicInput(Vec<CircuitField>);
 
 impl From<[u8; 32]> for PublicInput {
     fn from(value: [u8; 32]) -> Self {
-        let size: usize = value.len();
-        let mut input = vec![CircuitField::zero(); 8 * size];
-        for i in 0..32 {
-            for j in 0..8 {
-                if value[i] >> j & 1 == 1 {
-                    input[i * 8 + j] = CircuitField::one();
-                }
-            }
-        }
-        PublicInput(input)
+        PublicInput(pack_hash(&value).to_vec())
     }
 }
 
-pub struct PublicGuessInput(Vec<Fp<MontBackend<FrConfig, 4>, 4>>);
+pub struct PublicGuessInput(Vec<CircuitField>);
 
 impl From<([u8; CODE_LENGTH], [u8; 32], u8, u8)> for PublicGuessInput {
     fn from(value: ([u8; CODE_LENGTH], [u8; 32], u8, u8)) -> Self {
-        let size_guess: usize = value.0.len();
-        let mut input_guess: Vec<Fp<MontBackend<FrConfig, 4>, 4>> = vec![CircuitField::zero(); 0];
-        for i in 0..CODE_LENGTH {
-            input_guess.push(value.0[i].into());
+        let mut input: Vec<CircuitField> = value.0.map(CircuitField::from).to_vec();
+        input.extend(pack_hash(&value.1));
+        input.push(value.2.into());
+        input.push(value.3.into());
+        PublicGuessInput(input)
+    }
+}
+
+
+pub struct Proof{
+    proof: ark_groth16::Proof<Curve>,
+    // as reported by the prover, for display only
+    pub proving_time: Duration,
+    pub constraints: usize,
+}
+
+impl Proof {
+    // the compressed Groth16 proof
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.proof.compressed_size());
+        self.proof.serialize_compressed(&mut bytes).expect("writing to a vector cannot fail");
+        bytes
+    }
+
+    // as exported, without what the prover reported; read back only by --verify-daily
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Proof, SerializationError> {
+        let proof = CanonicalDeserialize::deserialize_compressed(bytes)?;
+        Ok(Proof { proof, proving_time: Duration::ZERO, constraints: 0 })
+    }
+
+    // the proof and what the prover reported, as a proving server sends it
+    #[cfg(any(test, not(target_arch = "wasm32")))]
+    pub fn to_wire(&self) -> Vec<u8> {
+        let mut bytes = self.to_bytes();
+        (self.proving_time.as_micros() as u64).serialize_compressed(&mut bytes).expect("writing to a vector cannot fail");
+        (self.constraints as u64).serialize_compressed(&mut bytes).expect("writing to a vector cannot fail");
+        bytes
+    }
+
+    // the points are checked to be on the curve and in the right subgroup
+    pub fn from_wire(mut bytes: &[u8]) -> Result<Proof, SerializationError> {
+        let proof = CanonicalDeserialize::deserialize_compressed(&mut bytes)?;
+        let micros = u64::deserialize_compressed(&mut bytes)?;
+        let constraints = u64::deserialize_compressed(&mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(SerializationError::InvalidData);
         }
+        Ok(Proof { proof, proving_time: Duration::from_micros(micros), constraints: constraints as usize })
+    }
+}
+
+#[derive(Debug)]
+pub enum ProvingError {
+    Synthesis(SynthesisError),
+    // the proving server could not be reached or did not prove
+    Server(String),
+}
+
+impl fmt::Display for ProvingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProvingError::Synthesis(err) => write!(f, "{}", err),
+            ProvingError::Server(err) => write!(f, "proving server: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ProvingError {}
+
+impl From<SynthesisError> for ProvingError {
+    fn from(err: SynthesisError) -> Self {
+        ProvingError::Synthesis(err)
+    }
+}
 
-        let size: usize = value.1.len();
-        let mut input = vec![CircuitField::zero(); 8 * size];
-        for i in 0..32 {
-            for j in 0..8 {
-                if value.1[i] >> j & 1 == 1 {
-                    input[i * 8 + j] = CircuitField::one();
-                }
+static CODE_CONSTRAINTS: OnceLock<usize> = OnceLock::new();
+static GUESS_CONSTRAINTS: OnceLock<usize> = OnceLock::new();
+
+// the count only depends on the circuit's shape, so each circuit is counted once
+fn count_constraints<C: ConstraintSynthesizer<CircuitField>>(count: &OnceLock<usize>, circuit: C) -> Result<usize, SynthesisError> {
+    if let Some(constraints) = count.get() {
+        return Ok(*constraints);
+    }
+    let cs = ConstraintSystem::new_ref();
+    circuit.generate_constraints(cs.clone())?;
+    Ok(*count.get_or_init(|| cs.num_constraints()))
+}
+
+static CODE_KEY: OnceLock<Key> = OnceLock::new();
+static GUESS_KEY: OnceLock<Key> = OnceLock::new();
+
+// any opening gives the circuits their shape, which is all the setup looks at
+const SHAPE: Opening = Opening { code: Code { colors: [0; CODE_LENGTH] }, salt: [0; 32] };
+
+// one setup per circuit and process, from fresh randomness that is dropped right after, unless
+// `use_keys` got there first. Proofs are only ever checked against these keys, never against a key
+// that comes with a proof, so no host, here or behind a proving server, knows a trapdoor to forge them with
+fn setup<C: ConstraintSynthesizer<CircuitField>>(key: &OnceLock<Key>, circuit: C) -> &Key {
+    key.get_or_init(|| {
+        Groth16::<Curve>::circuit_specific_setup(circuit, &mut StdRng::from_entropy())
+            .expect("the circuits synthesize without an assignment")
+            .0
+    })
+}
+
+pub fn code_key() -> &'static Key {
+    setup(&CODE_KEY, CodeDeclarationCircuit::from(&SHAPE))
+}
+
+pub fn guess_key() -> &'static Key {
+    setup(&GUESS_KEY, GuessCircuit::new(&SHAPE, SHAPE.code, Feedback { black: CODE_LENGTH as u8, white: 0 }))
+}
+
+// names a key, so a proving server can tell the keys it was given apart
+pub fn key_id(key: &Key) -> String {
+    hex(&Sha256::digest(verifier_to_bytes(&key.vk)))
+}
+
+pub fn verifier_to_bytes(vk: &Verifier) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(vk.compressed_size());
+    vk.serialize_compressed(&mut bytes).expect("writing to a vector cannot fail");
+    bytes
+}
+
+// the keys in the file, or this process's own written there when there is no such file yet; so
+// the publisher of the daily commitment hands out the keys every player proves and verifies with.
+// Whoever set them up could forge proofs for them, which the publisher, knowing the day's code
+// anyway, gains nothing by
+#[cfg(not(target_arch = "wasm32"))]
+pub fn use_keys(path: &std::path::Path) -> Result<(), String> {
+    let error = |err: &dyn fmt::Display| format!("{}: {}", path.display(), err);
+    match std::fs::read(path) {
+        Ok(bytes) => {
+            // checking all of the proving keys' points takes most of a minute; a broken one only
+            // spoils the proofs made with it, so only the verifying keys are
+            let mut reader = &bytes[..];
+            let mut read = || {
+                let key = Key::deserialize_compressed_unchecked(&mut reader)?;
+                ark_serialize::Valid::check(&key.vk)?;
+                Ok::<_, SerializationError>(key)
+            };
+            let code = read().map_err(|err| error(&err))?;
+            let guess = read().map_err(|err| error(&err))?;
+            if CODE_KEY.set(code).is_err() || GUESS_KEY.set(guess).is_err() {
+                return Err(error(&"the keys are already set up"));
             }
+            Ok(())
         }
-        input.push(value.2.into());
-        input.push(value.3.into());
-        input_guess.append(&mut input);
-        PublicGuessInput(input_guess)
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            let mut bytes = Vec::new();
+            code_key().serialize_compressed(&mut bytes).expect("writing to a vector cannot fail");
+            guess_key().serialize_compressed(&mut bytes).expect("writing to a vector cannot fail");
+            std::fs::write(path, bytes).map_err(|err| error(&err))
+        }
+        Err(err) => Err(error(&err)),
     }
 }
 
+fn prove_circuit<C, R>(count: &OnceLock<usize>, key: &Key, circuit: C, rng: &mut R) -> Result<Proof, SynthesisError>
+where
+    C: ConstraintSynthesizer<CircuitField> + Clone,
+    R: RngCore + CryptoRng,
+{
+    let constraints = count_constraints(count, circuit.clone())?;
+    let start = Instant::now();
+    let proof = Groth16::<_, LibsnarkReduction>::prove(key, circuit, rng)?;
+    Ok(Proof{proof, proving_time: start.elapsed(), constraints})
+}
 
-pub struct Proof{
-    proof: ark_groth16::Proof<ark_ec::bls12::Bls12<ark_bls12_381::Config>>,
-    vk: VerifyingKey<Bls12<Config>>,
+// with a proving server the key goes there, and the proof is made with randomness drawn
+// from `rng` just as it is here
+pub fn prove<R: RngCore + CryptoRng>(opening: &Opening, rng: &mut R) -> Result<Proof, ProvingError> {
+    let request = ProvingRequest::Code { opening: *opening, seed: remote::proving_seed(rng) };
+    match remote::server() {
+        Some(server) => remote::prove(server, code_key(), &request),
+        None => request.prove(code_key()),
+    }
 }
 
-pub fn prove(code:Code, salt: [u8; 32], hash: [u8; 32]) -> Proof {
-    let circuit = CodeDeclarationCircuit{code, salt, hash};
+pub fn prove_guess<R: RngCore + CryptoRng>(opening: &Opening, guess: Code, feedback: Feedback, rng: &mut R) -> Result<Proof, ProvingError> {
+    let request = ProvingRequest::Guess { opening: *opening, guess, feedback, seed: remote::proving_seed(rng) };
+    match remote::server() {
+        Some(server) => remote::prove(server, guess_key(), &request),
+        None => request.prove(guess_key()),
+    }
+}
 
-    let mut rng = StdRng::seed_from_u64(1);
-    let (pk, vk) =
-        Groth16::<Curve>::circuit_specific_setup(circuit.clone(), &mut rng).unwrap();
-    let proof = Groth16::<_, LibsnarkReduction>::prove(&pk, circuit.clone(), &mut rng).unwrap();
-    Proof{proof, vk}
+// proves with a given key, which a proving server gets from its client
+pub fn prove_with<R: RngCore + CryptoRng>(key: &Key, opening: &Opening, rng: &mut R) -> Result<Proof, ProvingError> {
+    Ok(prove_circuit(&CODE_CONSTRAINTS, key, CodeDeclarationCircuit::from(opening), rng)?)
 }
 
-pub fn verify(hash: [u8; 32], proof: Proof) -> bool{
-    let input = PublicInput::from(hash);
-    Groth16::<_, LibsnarkReduction>::verify(&proof.vk, &input.0, &proof.proof).unwrap()
+pub fn prove_guess_with<R: RngCore + CryptoRng>(key: &Key, opening: &Opening, guess: Code, feedback: Feedback, rng: &mut R) -> Result<Proof, ProvingError> {
+    Ok(prove_circuit(&GUESS_CONSTRAINTS, key, GuessCircuit::new(opening, guess, feedback), rng)?)
 }
 
-pub fn prove_guess(code:Code, guess:Code, salt: [u8; 32], hash: [u8; 32], correct: u8, common:u8) -> Proof {
-    let circuit = GuessCircuit{code, guess, salt, hash, correct, common};
+// against this process's own key; malformed inputs count as a failed verification rather than an error
+pub fn verify(commitment: &Commitment, proof: &Proof) -> bool{
+    let input = PublicInput::from(commitment.hash);
+    Groth16::<_, LibsnarkReduction>::verify(&code_key().vk, &input.0, &proof.proof).unwrap_or(false)
+}
 
-    let mut rng = StdRng::seed_from_u64(1);
-    let (pk, vk) =
-        Groth16::<Curve>::circuit_specific_setup(circuit.clone(), &mut rng).unwrap();
-    let proof = Groth16::<_, LibsnarkReduction>::prove(&pk, circuit.clone(), &mut rng).unwrap();
-    Proof{proof, vk}
+pub fn verify_guess(guess: Code, commitment: &Commitment, feedback: Feedback, proof: &Proof) -> bool{
+    let input = PublicGuessInput::from((guess.colors, commitment.hash, feedback.correct(), feedback.common()));
+    Groth16::<_, LibsnarkReduction>::verify(&guess_key().vk, &input.0, &proof.proof).unwrap_or(false)
 }
 
-pub fn verify_guess(guess: [u8; CODE_LENGTH], hash: [u8; 32], correct: u8, common: u8, proof: Proof) -> bool{
-    let input = PublicGuessInput::from((guess, hash, correct, common));
-    Groth16::<_, LibsnarkReduction>::verify(&proof.vk, &input.0, &proof.proof).unwrap()
+#[cfg(test)]
+mod tests {
+    // the same tests run in the browser build, see .cargo/config.toml
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+    use crate::host::{HonestHost, Host};
+    use crate::inspector::Statement;
+    use crate::seed::GameSeed;
+    use super::*;
+
+    #[test]
+    fn commitments_verify() {
+        let mut host = HonestHost::new(GameSeed::reproducible(1));
+        let (commitment, proof) = host.get_hash_with_proof().unwrap();
+        assert!(Statement::Commitment(commitment).verify(&proof));
+        assert!(!Statement::Commitment(Commitment { hash: [0; 32] }).verify(&proof));
+        // as it comes back from a proving server
+        let received = Proof::from_wire(&proof.to_wire()).unwrap();
+        assert!(Statement::Commitment(commitment).verify(&received));
+        assert!(Proof::from_wire(&[proof.to_wire(), vec![0]].concat()).is_err());
+    }
+
+    #[test]
+    fn wrong_answers_do_not_verify() {
+        let mut host = HonestHost::with_code("abcd".parse().unwrap(), GameSeed::reproducible(2));
+        let (commitment, _) = host.get_hash_with_proof().unwrap();
+        let guess: Code = "abdc".parse().unwrap();
+        let (answer, proof) = host.guess(guess).unwrap();
+        assert_eq!(answer, Feedback::new(2, 2).unwrap());
+        assert!(Statement::Guess { guess, commitment, answer }.verify(&proof));
+        let claimed = Feedback::new(4, 0).unwrap();
+        assert!(!Statement::Guess { guess, commitment, answer: claimed }.verify(&proof));
+    }
 }
 
 /*
@@ -0,0 +1,165 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::RngCore;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use crate::code::Code;
+use crate::commitment::Opening;
+use crate::feedback::Feedback;
+use crate::inspector::{hex, unhex};
+use crate::proof::{self, Key, Proof, ProvingError};
+
+// host:port of a process started with --serve, which then makes every proof with the keys of this
+// process; set once at startup
+static SERVER: OnceLock<String> = OnceLock::new();
+
+pub fn set_server(addr: String) {
+    SERVER.set(addr).ok();
+}
+
+pub fn server() -> Option<&'static str> {
+    SERVER.get().map(String::as_str)
+}
+
+// the server seeds its proving randomness with this, so it still comes from the host's rng
+pub fn proving_seed<R: RngCore>(rng: &mut R) -> [u8; 32] {
+    let mut seed = [0; 32];
+    rng.fill_bytes(&mut seed);
+    seed
+}
+
+// everything one proof needs, the opening included, so a proving server learns the code: it is
+// meant to run on the player's own machine, which could look into a host proving locally just as well
+#[derive(Clone, Copy, Debug)]
+pub enum ProvingRequest {
+    Code { opening: Opening, seed: [u8; 32] },
+    Guess { opening: Opening, guess: Code, feedback: Feedback, seed: [u8; 32] },
+}
+
+impl ProvingRequest {
+    pub fn prove(&self, key: &Key) -> Result<Proof, ProvingError> {
+        match *self {
+            ProvingRequest::Code { opening, seed } => proof::prove_with(key, &opening, &mut StdRng::from_seed(seed)),
+            ProvingRequest::Guess { opening, guess, feedback, seed } => {
+                proof::prove_guess_with(key, &opening, guess, feedback, &mut StdRng::from_seed(seed))
+            }
+        }
+    }
+}
+
+// one line, e.g. "guess abcd <salt> <seed> aabb 1 2" for an answer of one black and two white pegs
+impl fmt::Display for ProvingRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProvingRequest::Code { opening, seed } => write!(f, "code {} {} {}", opening.code, hex(&opening.salt), hex(seed)),
+            ProvingRequest::Guess { opening, guess, feedback, seed } => write!(f, "guess {} {} {} {} {} {}",
+                opening.code, hex(&opening.salt), hex(seed), guess, feedback.black, feedback.white),
+        }
+    }
+}
+
+fn bytes32(text: &str) -> Result<[u8; 32], String> {
+    unhex(text).and_then(|bytes| bytes.try_into().ok()).ok_or_else(|| format!("not 32 bytes of hex: {:?}", text))
+}
+
+impl FromStr for ProvingRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let [_, code, salt, seed, ..] = words[..] else {
+            return Err(format!("not a proving request: {:?}", s));
+        };
+        let opening = Opening { code: code.parse().map_err(|err| format!("{}", err))?, salt: bytes32(salt)? };
+        let seed = bytes32(seed)?;
+        match words[..] {
+            ["code", _, _, _] => Ok(ProvingRequest::Code { opening, seed }),
+            ["guess", _, _, _, guess, black, white] => {
+                let guess = guess.parse().map_err(|err| format!("{}", err))?;
+                let pegs = |pegs: &str| pegs.parse().map_err(|_| format!("not a number of pegs: {:?}", pegs));
+                let feedback = Feedback::new(pegs(black)?, pegs(white)?).map_err(|err| err.to_string())?;
+                Ok(ProvingRequest::Guess { opening, guess, feedback, seed })
+            }
+            _ => Err(format!("not a proving request: {:?}", s)),
+        }
+    }
+}
+
+// the server answers 409 when it does not hold the key, not yet or no longer, which is then sent along
+pub fn prove(server: &str, key: &Key, request: &ProvingRequest) -> Result<Proof, ProvingError> {
+    let line = format!("{} {}", proof::key_id(key), request);
+    let (mut status, mut body) = post(server, "/prove", line.as_bytes()).map_err(ProvingError::Server)?;
+    if status == 409 {
+        upload(server, key)?;
+        (status, body) = post(server, "/prove", line.as_bytes()).map_err(ProvingError::Server)?;
+    }
+    if status != 200 {
+        return Err(ProvingError::Server(format!("{} {}", status, body.trim())));
+    }
+    let bytes = unhex(body.trim()).ok_or_else(|| ProvingError::Server("the answer is not a proof".to_owned()))?;
+    Proof::from_wire(&bytes).map_err(|err| ProvingError::Server(format!("malformed proof: {}", err)))
+}
+
+fn upload(server: &str, key: &Key) -> Result<(), ProvingError> {
+    let mut bytes = Vec::with_capacity(key.compressed_size());
+    key.serialize_compressed(&mut bytes).expect("writing to a vector cannot fail");
+    match post(server, "/key", &bytes).map_err(ProvingError::Server)? {
+        (200, _) => Ok(()),
+        (status, body) => Err(ProvingError::Server(format!("{} {}", status, body.trim()))),
+    }
+}
+
+// one request per connection, which is all the server speaks
+#[cfg(not(target_arch = "wasm32"))]
+fn post(server: &str, path: &str, body: &[u8]) -> Result<(u16, String), String> {
+    use std::io::{Read, Write};
+    let mut stream = std::net::TcpStream::connect(server).map_err(|err| format!("{}: {}", server, err))?;
+    write!(stream, "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        path, server, body.len()).map_err(|err| err.to_string())?;
+    stream.write_all(body).map_err(|err| err.to_string())?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).map_err(|err| err.to_string())?;
+    let (head, body) = response.split_once("\r\n\r\n").ok_or_else(|| "malformed HTTP response".to_owned())?;
+    let status = head.split_whitespace().nth(1).and_then(|status| status.parse().ok()).ok_or_else(|| "malformed HTTP response".to_owned())?;
+    Ok((status, body.to_owned()))
+}
+
+// synchronous, as the host waiting for the proof cannot take a callback; the frame stalls
+// for it as it would for proving in the browser, only much shorter
+#[cfg(target_arch = "wasm32")]
+fn post(server: &str, path: &str, body: &[u8]) -> Result<(u16, String), String> {
+    let js_error = |err: wasm_bindgen::JsValue| format!("{:?}", err);
+    let request = web_sys::XmlHttpRequest::new().map_err(js_error)?;
+    request.open_with_async("POST", &format!("http://{}{}", server, path), false).map_err(js_error)?;
+    // plain text keeps it a simple cross-origin request, without a preflight
+    request.set_request_header("Content-Type", "text/plain").map_err(js_error)?;
+    request.send_with_opt_u8_array(Some(body)).map_err(js_error)?;
+    let text = request.response_text().map_err(js_error)?.unwrap_or_default();
+    Ok((request.status().map_err(js_error)?, text))
+}
+
+#[cfg(test)]
+mod tests {
+    // the same tests run in the browser build, see .cargo/config.toml
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+    use crate::host::random_opening;
+    use crate::seed::GameSeed;
+    use super::*;
+
+    #[test]
+    fn proving_requests_read_back() {
+        let mut rng = GameSeed::reproducible(3).secret_rng();
+        let request = ProvingRequest::Guess {
+            opening: random_opening(GameSeed::reproducible(3)),
+            guess: Code::random(&mut rng),
+            feedback: Feedback::new(1, 2).unwrap(),
+            seed: proving_seed(&mut rng),
+        };
+        let line = request.to_string();
+        assert_eq!(line.parse::<ProvingRequest>().unwrap().to_string(), line);
+        assert!("guess abcd".parse::<ProvingRequest>().is_err());
+    }
+}
@@ -0,0 +1,222 @@
+use ark_bls12_381::Fr;
+use ark_r1cs_std::boolean::Boolean;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::fields::FieldVar;
+use ark_relations::r1cs::{ConstraintSystemRef, Result};
+
+use crate::code::Code;
+use crate::crypto::{min_count, one_hot, CODE_LENGTH, COLOR_NUMBER};
+use crate::feedback::Feedback;
+
+type CircuitField = Fr;
+
+// what `score` needs from its numbers: natively plain integers, in the circuit
+// variables whose operations add constraints
+pub trait ScoreDomain {
+    type Color;
+    type Bit;
+    type Count: Clone;
+
+    fn one_hot(&mut self, color: &Self::Color) -> Result<[Self::Bit; COLOR_NUMBER]>;
+    fn and(&mut self, a: &Self::Bit, b: &Self::Bit) -> Result<Self::Bit>;
+    fn zero(&self) -> Self::Count;
+    fn add_bit(&self, count: Self::Count, bit: &Self::Bit) -> Self::Count;
+    fn add(&self, a: Self::Count, b: &Self::Count) -> Self::Count;
+    fn min(&mut self, a: &Self::Count, b: &Self::Count) -> Result<Self::Count>;
+}
+
+// the one definition of the answer to a guess, as (correct, common) where common
+// also counts the correct ones: exact matches per position, and per color the
+// smaller of the two counts
+pub fn score<D: ScoreDomain>(
+    domain: &mut D,
+    code: &[D::Color; CODE_LENGTH],
+    guess: &[D::Color; CODE_LENGTH],
+) -> Result<(D::Count, D::Count)> {
+    let code_hot = code.iter().map(|col| domain.one_hot(col)).collect::<Result<Vec<_>>>()?;
+    let guess_hot = guess.iter().map(|col| domain.one_hot(col)).collect::<Result<Vec<_>>>()?;
+
+    let mut correct = domain.zero();
+    for (code, guess) in code_hot.iter().zip(&guess_hot) {
+        for (c, g) in code.iter().zip(guess) {
+            let both = domain.and(c, g)?;
+            correct = domain.add_bit(correct, &both);
+        }
+    }
+
+    let mut common = domain.zero();
+    for color in 0..COLOR_NUMBER {
+        let code_count = code_hot.iter().fold(domain.zero(), |sum, hot| domain.add_bit(sum, &hot[color]));
+        let guess_count = guess_hot.iter().fold(domain.zero(), |sum, hot| domain.add_bit(sum, &hot[color]));
+        let min = domain.min(&code_count, &guess_count)?;
+        common = domain.add(common, &min);
+    }
+    Ok((correct, common))
+}
+
+pub struct Native;
+
+impl ScoreDomain for Native {
+    type Color = u8;
+    type Bit = bool;
+    type Count = u8;
+
+    fn one_hot(&mut self, color: &u8) -> Result<[bool; COLOR_NUMBER]> {
+        Ok(core::array::from_fn(|c| c == *color as usize))
+    }
+
+    fn and(&mut self, a: &bool, b: &bool) -> Result<bool> {
+        Ok(*a && *b)
+    }
+
+    fn zero(&self) -> u8 {
+        0
+    }
+
+    fn add_bit(&self, count: u8, bit: &bool) -> u8 {
+        count + *bit as u8
+    }
+
+    fn add(&self, a: u8, b: &u8) -> u8 {
+        a + b
+    }
+
+    fn min(&mut self, a: &u8, b: &u8) -> Result<u8> {
+        Ok(*a.min(b))
+    }
+}
+
+pub struct Circuit {
+    cs: ConstraintSystemRef<CircuitField>,
+}
+
+impl Circuit {
+    pub fn new(cs: ConstraintSystemRef<CircuitField>) -> Self {
+        Circuit { cs }
+    }
+}
+
+impl ScoreDomain for Circuit {
+    type Color = FpVar<CircuitField>;
+    type Bit = Boolean<CircuitField>;
+    type Count = FpVar<CircuitField>;
+
+    fn one_hot(&mut self, color: &Self::Color) -> Result<[Self::Bit; COLOR_NUMBER]> {
+        one_hot(self.cs.clone(), color)
+    }
+
+    fn and(&mut self, a: &Self::Bit, b: &Self::Bit) -> Result<Self::Bit> {
+        a.and(b)
+    }
+
+    fn zero(&self) -> Self::Count {
+        FpVar::zero()
+    }
+
+    fn add_bit(&self, count: Self::Count, bit: &Self::Bit) -> Self::Count {
+        count + FpVar::from(bit.clone())
+    }
+
+    fn add(&self, a: Self::Count, b: &Self::Count) -> Self::Count {
+        a + b
+    }
+
+    fn min(&mut self, a: &Self::Count, b: &Self::Count) -> Result<Self::Count> {
+        min_count(self.cs.clone(), a, b)
+    }
+}
+
+pub fn feedback(secret: &Code, guess: &Code) -> Feedback {
+    let (correct, common) = score(&mut Native, &secret.colors, &guess.colors).unwrap();
+    Feedback::from_circuit(correct, common).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use ark_r1cs_std::alloc::AllocVar;
+    use ark_r1cs_std::eq::EqGadget;
+    use ark_r1cs_std::select::CondSelectGadget;
+    use ark_r1cs_std::R1CSVar;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use crate::commitment::Opening;
+    use crate::feedback::ANSWER_COUNT;
+    use crate::guess_circuit::GuessCircuit;
+    use super::*;
+
+    type Colors = [FpVar<CircuitField>; CODE_LENGTH];
+
+    // the scoring `GuessCircuit` had before one-hot colors: an is_eq for every color and position,
+    // twice over, and an is_cmp for every color's minimum
+    fn per_color_score(code: &Colors, guess: &Colors) -> Result<(FpVar<CircuitField>, FpVar<CircuitField>)> {
+        let mut correct = FpVar::zero();
+        for (code, guess) in code.iter().zip(guess) {
+            let is_equal = code.is_eq(guess)?;
+            correct = FpVar::conditionally_select(&is_equal, &(&correct + FpVar::one()), &correct)?;
+        }
+        let count = |colors: &Colors, color: usize| -> Result<FpVar<CircuitField>> {
+            let mut sum = FpVar::zero();
+            for col in colors {
+                let is_equal = col.is_eq(&FpVar::constant(CircuitField::from(color as u8)))?;
+                sum = FpVar::conditionally_select(&is_equal, &(&sum + FpVar::one()), &sum)?;
+            }
+            Ok(sum)
+        };
+        let mut common = FpVar::zero();
+        for color in 0..COLOR_NUMBER {
+            let (code_count, guess_count) = (count(code, color)?, count(guess, color)?);
+            let code_smaller = code_count.is_cmp(&guess_count, Ordering::Less, true)?;
+            common = FpVar::conditionally_select(&code_smaller, &(&common + &code_count), &(&common + &guess_count))?;
+        }
+        Ok((correct, common))
+    }
+
+    fn colors(cs: &ConstraintSystemRef<CircuitField>, code: &str) -> Colors {
+        let code: Code = code.parse().unwrap();
+        code.colors.map(|col| FpVar::new_witness(cs.clone(), || Ok(CircuitField::from(col))).unwrap())
+    }
+
+    // the scoring alone, without the hash that takes nearly all of a `GuessCircuit`: 160 constraints
+    // against 15,415
+    #[test]
+    fn one_hot_scoring_takes_fewer_constraints() {
+        let cs = ConstraintSystem::<CircuitField>::new_ref();
+        let (code, guess) = (colors(&cs, "aabc"), colors(&cs, "abca"));
+        let (correct, common) = score(&mut Circuit::new(cs.clone()), &code, &guess).unwrap();
+        let one_hot = cs.num_constraints();
+        let (old_correct, old_common) = per_color_score(&code, &guess).unwrap();
+        let per_color = cs.num_constraints() - one_hot;
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!((correct.value().unwrap(), common.value().unwrap()), (old_correct.value().unwrap(), old_common.value().unwrap()));
+        assert!(one_hot <= 200, "{} constraints", one_hot);
+        assert!(one_hot * 50 < per_color, "{} against {} constraints", one_hot, per_color);
+    }
+
+    fn satisfies(opening: &Opening, guess: Code, answer: Feedback) -> bool {
+        let cs = ConstraintSystem::<CircuitField>::new_ref();
+        GuessCircuit::new(opening, guess, answer).generate_constraints(cs.clone()).unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
+    // the host's answer is the only one the circuit accepts
+    #[test]
+    fn circuits_accept_only_the_native_answer() {
+        let mut rng = StdRng::seed_from_u64(34);
+        let answers: Vec<Feedback> = (0..ANSWER_COUNT).map(Feedback::from_index)
+            .filter(|answer| Feedback::new(answer.black, answer.white).is_ok())
+            .collect();
+        let mut pairs: Vec<(Code, Code)> = (0..12).map(|_| (Code::random(&mut rng), Code::random(&mut rng))).collect();
+        pairs.extend(["abcd", "aaaa", "aabb"].map(|code| (code.parse().unwrap(), code.parse().unwrap())));
+        pairs.push(("aabb".parse().unwrap(), "bbaa".parse().unwrap()));
+        pairs.push(("abcd".parse().unwrap(), "efgh".parse().unwrap()));
+        for (secret, guess) in pairs {
+            let opening = Opening::new(secret, &mut rng);
+            let honest = feedback(&secret, &guess);
+            for &answer in &answers {
+                assert_eq!(satisfies(&opening, guess, answer), answer == honest, "{} for {} against {}", answer, guess, secret);
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::fmt;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use sha2::{Digest, Sha256};
+
+// keeps the proving randomness apart from the secret's when both come from one seed
+const PROVING_STREAM: u64 = 0x9e37_79b9_7f4a_7c15;
+
+// where a game's randomness comes from. The secret and its salt always follow the seed, so a
+// reported seed brings back the same code and commitment; the proving randomness only follows
+// it in a game started with --seed and is fresh otherwise. The proofs differ between runs all
+// the same, as every run sets up its own keys. Knowing the seed means knowing the code, so it is
+// only shown once the game is over
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameSeed {
+    pub seed: u64,
+    pub reproducible: bool,
+}
+
+impl GameSeed {
+    pub fn fresh() -> Self {
+        GameSeed { seed: rand::random(), reproducible: false }
+    }
+
+    pub fn reproducible(seed: u64) -> Self {
+        GameSeed { seed, reproducible: true }
+    }
+
+    // the seed for part `index` of something played under this seed, e.g. a round of a match
+    pub fn derive(self, index: u64) -> Self {
+        GameSeed { seed: self.hash(b"part", index), ..self }
+    }
+
+    // the game after this one: still reproducible from the first seed, or fresh
+    pub fn next(self) -> Self {
+        if self.reproducible {
+            GameSeed { seed: self.hash(b"next", 0), ..self }
+        } else {
+            GameSeed::fresh()
+        }
+    }
+
+    // the label keeps the parts of a game apart from the games after it, which seeds close
+    // to each other would not
+    fn hash(self, label: &[u8], index: u64) -> u64 {
+        let mut hasher = Sha256::new();
+        hasher.update(label);
+        hasher.update(self.seed.to_le_bytes());
+        hasher.update(index.to_le_bytes());
+        u64::from_le_bytes(hasher.finalize()[..8].try_into().unwrap())
+    }
+
+    pub fn secret_rng(self) -> StdRng {
+        StdRng::seed_from_u64(self.seed)
+    }
+
+    pub fn proving_rng(self) -> StdRng {
+        if self.reproducible {
+            StdRng::seed_from_u64(self.seed ^ PROVING_STREAM)
+        } else {
+            StdRng::from_entropy()
+        }
+    }
+}
+
+impl fmt::Display for GameSeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.seed)
+    }
+}
@@ -0,0 +1,114 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use ark_serialize::CanonicalDeserialize;
+use crate::inspector::hex;
+use crate::proof::{self, Key};
+use crate::remote::ProvingRequest;
+
+// proving requests are single lines of text, anything longer is not one
+const MAX_REQUEST: usize = 4096;
+// a proving key of the larger circuit is some 10 MB
+const MAX_KEY: usize = 64 << 20;
+// keys of clients which are gone are dropped after this many newer ones
+const KEPT_KEYS: usize = 16;
+
+// the clients' proving keys by their ids, the newest last
+type Keys = Arc<Mutex<Vec<(String, Arc<Key>)>>>;
+
+// proves for others, mainly the browser build, over plain HTTP. A client first sends its proving key
+// with POST /key, then POST /prove with the key's id and a `ProvingRequest` answers with the hex of the
+// proof. The keys come from the client's own setup, so the server cannot forge proofs it will accept.
+// Any page may ask, so it should only listen on localhost
+pub fn serve(addr: &str) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    println!("Proving on http://{}/prove", listener.local_addr()?);
+    let keys = Keys::default();
+    for stream in listener.incoming() {
+        match stream {
+            // proofs take seconds, so each request gets a thread of its own
+            Ok(stream) => {
+                let keys = keys.clone();
+                std::thread::spawn(move || {
+                    if let Err(err) = answer(stream, &keys) {
+                        eprintln!("{}", err);
+                    }
+                });
+            }
+            Err(err) => eprintln!("{}", err),
+        }
+    }
+    Ok(())
+}
+
+fn answer(mut stream: TcpStream, keys: &Keys) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim_end().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let words: Vec<&str> = request_line.split_whitespace().collect();
+    let limit = if words.get(1) == Some(&"/key") { MAX_KEY } else { MAX_REQUEST };
+    let mut body = vec![0; length.min(limit)];
+    reader.read_exact(&mut body)?;
+    let (status, text) = match words[..] {
+        ["OPTIONS", ..] => ("204 No Content", String::new()),
+        ["POST", ..] if length > limit => ("413 Payload Too Large", "that is too much".to_owned()),
+        ["POST", "/key", ..] => receive_key(&body, keys),
+        ["POST", "/prove", ..] => prove(&String::from_utf8_lossy(&body), keys),
+        _ => ("404 Not Found", "POST /key and /prove is all there is".to_owned()),
+    };
+    write!(stream, "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\
+        Access-Control-Allow-Origin: *\r\nAccess-Control-Allow-Methods: POST\r\nAccess-Control-Allow-Headers: Content-Type\r\n\
+        Connection: close\r\n\r\n{}", status, text.len(), text)
+}
+
+// unchecked, as a broken key only spoils the proofs of whoever sent it
+fn receive_key(body: &[u8], keys: &Keys) -> (&'static str, String) {
+    match Key::deserialize_compressed_unchecked(body) {
+        Ok(key) => {
+            let id = proof::key_id(&key);
+            let mut keys = keys.lock().unwrap();
+            keys.retain(|(kept, _)| *kept != id);
+            keys.push((id.clone(), Arc::new(key)));
+            if keys.len() > KEPT_KEYS {
+                keys.remove(0);
+            }
+            eprintln!("received key {}", id);
+            ("200 OK", id)
+        }
+        Err(err) => ("400 Bad Request", format!("not a proving key: {}", err)),
+    }
+}
+
+fn prove(body: &str, keys: &Keys) -> (&'static str, String) {
+    let Some((id, request)) = body.trim().split_once(' ') else {
+        return ("400 Bad Request", format!("not a proving request: {:?}", body));
+    };
+    let request = match request.parse::<ProvingRequest>() {
+        Ok(request) => request,
+        Err(err) => return ("400 Bad Request", err),
+    };
+    let key = keys.lock().unwrap().iter().find(|(kept, _)| kept == id).map(|(_, key)| key.clone());
+    let Some(key) = key else {
+        return ("409 Conflict", format!("no key {}, send it to /key first", id));
+    };
+    eprintln!("proving {}", match request {
+        ProvingRequest::Code { .. } => "a commitment".to_owned(),
+        ProvingRequest::Guess { guess, feedback, .. } => format!("{} for {}", feedback, guess),
+    });
+    match request.prove(&key) {
+        Ok(proof) => ("200 OK", hex(&proof.to_wire())),
+        Err(err) => ("422 Unprocessable Entity", err.to_string()),
+    }
+}
@@ -0,0 +1,173 @@
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use crate::clock::TimeLimit;
+use crate::crypto::COLOR_NUMBER;
+use crate::GUESSES;
+
+// the most rows a board may have
+pub const MAX_GUESSES: usize = 16;
+
+// the computer's kind of codemaker on the board
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HostKind {
+    #[default]
+    Honest,
+    Hardest,
+    Evil,
+    Cheating,
+    Lazy,
+}
+
+impl HostKind {
+    pub const ALL: [HostKind; 5] = [HostKind::Honest, HostKind::Hardest, HostKind::Evil, HostKind::Cheating, HostKind::Lazy];
+
+    // the names the command line and ?host= take; None for anything else, which the command line
+    // refuses and the page leaves to the settings
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "honest" => Some(HostKind::Honest),
+            "hardest" => Some(HostKind::Hardest),
+            "evil" => Some(HostKind::Evil),
+            "cheating" => Some(HostKind::Cheating),
+            "lazy" => Some(HostKind::Lazy),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HostKind::Honest => "Honest",
+            HostKind::Hardest => "Honest, with a code needing the most guesses",
+            HostKind::Evil => "Evil, always answers no pegs",
+            HostKind::Cheating => "Cheating, gives the worst answer each time",
+            HostKind::Lazy => "Lazy, swaps its code when that hurts more",
+        }
+    }
+}
+
+// the mode the app opens in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Variant {
+    #[default]
+    BreakTheCode,
+    MakeTheCode,
+    Match,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Palette {
+    #[default]
+    Classic,
+    // Okabe & Ito's colors stay apart for the common kinds of color blindness
+    OkabeIto,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 2] = [Palette::Classic, Palette::OkabeIto];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Palette::Classic => "Classic",
+            Palette::OkabeIto => "Okabe–Ito (color-blind safe)",
+        }
+    }
+
+    // in the order of code::COLOR_NAMES, each palette keeps a color close to its name where it can
+    pub fn colors(self) -> [egui::Color32; COLOR_NUMBER] {
+        let rgb = match self {
+            Palette::Classic => [
+                (204, 0, 1), (251, 148, 11), (255, 255, 1), (1, 204, 0),
+                (3, 192, 198), (0, 0, 254), (118, 44, 167), (254, 152, 191),
+            ],
+            Palette::OkabeIto => [
+                (213, 94, 0), (230, 159, 0), (240, 228, 66), (0, 158, 115),
+                (86, 180, 233), (0, 114, 178), (204, 121, 167), (187, 187, 187),
+            ],
+        };
+        rgb.map(|(r, g, b)| egui::Color32::from_rgb(r, g, b))
+    }
+}
+
+// what is written on top of each peg, so colors need not be told apart at all
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PegLabels {
+    #[default]
+    None,
+    Letters,
+    Numbers,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub host: HostKind,
+    pub variant: Variant,
+    pub guesses: usize,
+    pub time_limit: TimeLimit,
+    pub palette: Palette,
+    pub labels: PegLabels,
+    // feedback as solid and hollow rings instead of black and white dots on gray
+    pub high_contrast_feedback: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            host: HostKind::default(),
+            variant: Variant::default(),
+            guesses: GUESSES,
+            time_limit: TimeLimit::default(),
+            palette: Palette::default(),
+            labels: PegLabels::default(),
+            high_contrast_feedback: false,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        let settings: Settings = storage.and_then(|storage| eframe::get_value(storage, eframe::APP_KEY)).unwrap_or_default();
+        Settings { guesses: settings.guesses.clamp(1, MAX_GUESSES), ..settings }
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, self);
+    }
+
+    // returns whether anything changed
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = *self;
+        ui.label("Opponent");
+        for host in HostKind::ALL {
+            ui.radio_value(&mut self.host, host, host.name());
+        }
+        ui.add(egui::Slider::new(&mut self.guesses, 1..=MAX_GUESSES).text("guesses"));
+        ui.label("Time limit");
+        let seconds = match self.time_limit {
+            TimeLimit::PerGame(seconds) | TimeLimit::PerGuess(seconds) => seconds,
+            TimeLimit::None => 60,
+        };
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.time_limit, TimeLimit::None, "None");
+            ui.radio_value(&mut self.time_limit, TimeLimit::PerGame(seconds), "Per game");
+            ui.radio_value(&mut self.time_limit, TimeLimit::PerGuess(seconds), "Per guess");
+        });
+        if let TimeLimit::PerGame(seconds) | TimeLimit::PerGuess(seconds) = &mut self.time_limit {
+            ui.add(egui::Slider::new(seconds, 10..=900).text("seconds"));
+        }
+        ui.label("These take effect with the next game.");
+        ui.separator();
+        ui.label("Palette");
+        for palette in Palette::ALL {
+            ui.radio_value(&mut self.palette, palette, palette.name());
+        }
+        ui.separator();
+        ui.label("Peg labels");
+        ui.radio_value(&mut self.labels, PegLabels::None, "None");
+        ui.radio_value(&mut self.labels, PegLabels::Letters, "Letters");
+        ui.radio_value(&mut self.labels, PegLabels::Numbers, "Numbers");
+        ui.separator();
+        ui.checkbox(&mut self.high_contrast_feedback, "High-contrast feedback pegs");
+        *self != before
+    }
+}
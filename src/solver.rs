@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+use crate::code::Code;
+use crate::candidates::Candidates;
+use crate::feedback::Feedback;
+
+// a codebreaker which always plays the first code consistent with all answers so far
+pub struct Solver {
+    candidates: Candidates,
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        Solver { candidates: Candidates::all() }
+    }
+
+    // None once the answers contradict each other
+    pub fn next_guess(&self) -> Option<Code> {
+        self.candidates.first()
+    }
+
+    pub fn learn(&mut self, guess: &Code, answer: Feedback) {
+        self.candidates.retain(guess, answer);
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.candidates.len()
+    }
+}
+
+// number of guesses `Solver` needs for every secret, read off its decision tree
+pub fn guesses_per_secret() -> HashMap<Code, usize> {
+    let mut guesses = HashMap::new();
+    explore(Candidates::all(), 1, &mut guesses);
+    guesses
+}
+
+fn explore(candidates: Candidates, depth: usize, guesses: &mut HashMap<Code, usize>) {
+    let Some(guess) = candidates.first() else {
+        return;
+    };
+    guesses.insert(guess, depth);
+    for (answer, rest) in candidates.partition(&guess) {
+        if !answer.is_win() {
+            explore(rest, depth + 1, guesses);
+        }
+    }
+}
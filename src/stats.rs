@@ -0,0 +1,97 @@
+use std::time::Duration;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+const STATS_KEY: &str = "stats";
+
+// how the player fared against the computer on the board, kept across runs
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub games: usize,
+    // including games the host forfeited by cheating
+    pub wins: usize,
+    // broken codes by the guesses they took, the first entry for one guess
+    pub histogram: Vec<usize>,
+    pub cheats: usize,
+    pub proofs: usize,
+    pub proving_time: Duration,
+}
+
+impl Stats {
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        storage.and_then(|storage| eframe::get_value(storage, STATS_KEY)).unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, STATS_KEY, self);
+    }
+
+    pub fn record_proof(&mut self, proving_time: Duration) {
+        self.proofs += 1;
+        self.proving_time += proving_time;
+    }
+
+    // `broken` is the number of guesses it took, if the player broke the code
+    pub fn record_game(&mut self, broken: Option<usize>, cheated: bool) {
+        self.games += 1;
+        if cheated {
+            self.cheats += 1;
+            self.wins += 1;
+        } else if let Some(guesses) = broken {
+            self.wins += 1;
+            if self.histogram.len() < guesses {
+                self.histogram.resize(guesses, 0);
+            }
+            self.histogram[guesses - 1] += 1;
+        }
+    }
+
+    pub fn win_rate(&self) -> Option<f64> {
+        (self.games > 0).then(|| self.wins as f64 / self.games as f64)
+    }
+
+    pub fn average_proving_time(&self) -> Option<Duration> {
+        (self.proofs > 0).then(|| self.proving_time / self.proofs as u32)
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Statistics");
+        egui::Grid::new("stats").num_columns(2).show(ui, |ui| {
+            ui.label("games played");
+            ui.label(self.games.to_string());
+            ui.end_row();
+            ui.label("won");
+            ui.label(match self.win_rate() {
+                Some(rate) => format!("{} ({:.0}%)", self.wins, rate * 100.0),
+                None => "-".to_string(),
+            });
+            ui.end_row();
+            ui.label("cheats detected");
+            ui.label(self.cheats.to_string());
+            ui.end_row();
+            ui.label("average proving time");
+            ui.label(match self.average_proving_time() {
+                Some(time) => format!("{:.2?} over {} proofs", time, self.proofs),
+                None => "-".to_string(),
+            });
+            ui.end_row();
+        });
+        ui.add_space(10.0);
+        ui.label("Codes broken by number of guesses");
+        let most = self.histogram.iter().copied().max().unwrap_or(0).max(1);
+        for (i, &count) in self.histogram.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.monospace(format!("{:>2}", i + 1));
+                let width = 200.0 * count as f32 / most as f32;
+                let (rect, _) = ui.allocate_exact_size(egui::Vec2::new(width, 12.0), egui::Sense::hover());
+                ui.painter().rect_filled(rect, 2.0, ui.visuals().selection.bg_fill);
+                ui.label(count.to_string());
+            });
+        }
+        ui.add_space(10.0);
+        if ui.button("Reset statistics").clicked() {
+            *self = Stats::default();
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use crate::daily::{self, Daily};
+use crate::match_play;
+use crate::remote;
+use crate::seed::GameSeed;
+use crate::settings::{HostKind, MAX_GUESSES};
+use crate::Launch;
+
+// the id of the canvas in index.html
+const CANVAS_ID: &str = "zk_mastermind";
+
+// the page's query string stands in for the command line, e.g. ?seed=42&prover=127.0.0.1:9090
+fn launch() -> Launch {
+    let search = web_sys::window().and_then(|window| window.location().search().ok()).unwrap_or_default();
+    let mut launch = Launch { host: None, guesses: None, seed: GameSeed::fresh(), daily: None, two_windows: false, match_rounds: None };
+    let mut is_daily = false;
+    let mut date = None;
+    for pair in search.trim_start_matches('?').split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        match key {
+            "host" => launch.host = HostKind::from_name(value),
+            "guesses" => launch.guesses = value.parse().ok().filter(|n| (1..=MAX_GUESSES).contains(n)),
+            "seed" => launch.seed = value.parse().map_or(launch.seed, GameSeed::reproducible),
+            "daily" => is_daily = true,
+            "date" => date = value.parse().ok(),
+            "match" => launch.match_rounds = value.parse().ok().filter(|n| match_play::is_fair(*n)),
+            "two-windows" => launch.two_windows = true,
+            // proving in the browser takes a single thread, a local `zk-mastermind --serve` is much faster
+            "prover" => remote::set_server(value.to_owned()),
+            _ => {}
+        }
+    }
+    launch.daily = (is_daily || date.is_some()).then(|| Daily::new(&daily::secret(), date.unwrap_or_else(daily::today)));
+    launch
+}
+
+pub fn start() {
+    let launch = launch();
+    wasm_bindgen_futures::spawn_local(async move {
+        eframe::WebRunner::new()
+            .start(CANVAS_ID, eframe::WebOptions::default(), Box::new(|cc| Box::new(launch.app(cc))))
+            .await
+            .expect("failed to start the app");
+    });
+}